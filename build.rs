@@ -0,0 +1,35 @@
+//! Extract the module documentation of every check, so the binary can describe checks in SARIF
+//! logs without embedding their source code
+
+use std::{env, ffi::OsStr, fs, path::PathBuf};
+
+fn main() -> std::io::Result<()> {
+    let checks = PathBuf::from("src/checks");
+    let out_dir =
+        PathBuf::from(env::var_os("OUT_DIR").expect("OUT_DIR is set by cargo")).join("checks");
+    fs::create_dir_all(&out_dir)?;
+
+    println!("cargo:rerun-if-changed={}", checks.display());
+
+    for entry in fs::read_dir(&checks)? {
+        let path = entry?.path();
+        if path.extension() != Some(OsStr::new("rs")) {
+            continue;
+        }
+
+        let source = fs::read_to_string(&path)?;
+        let docs = source
+            .lines()
+            .map_while(|line| line.strip_prefix("//!"))
+            .map(|line| line.strip_prefix(' ').unwrap_or(line))
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        fs::write(
+            out_dir.join(path.with_extension("md").file_name().unwrap()),
+            docs.trim(),
+        )?;
+    }
+
+    Ok(())
+}
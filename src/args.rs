@@ -1,14 +1,18 @@
-use std::str::FromStr;
+use std::{path::PathBuf, str::FromStr};
 
-use anyhow::bail;
-use clap::Parser;
+use clap::{Parser, Subcommand};
 use secure_string::SecureString;
+use serde::{de::Error as _, Deserialize, Deserializer};
 use strum::IntoEnumIterator;
 
-use crate::checks::{AccountChecks, Checks, RepositoryChecks};
+use crate::{
+    checks::{AccountChecks, Checks, OrganizationChecks, RepositoryChecks},
+    findings::Severity,
+};
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default)]
 pub enum CheckRunRequest {
+    #[default]
     All,
     Specific(Vec<Checks>),
 }
@@ -24,12 +28,6 @@ impl CheckRunRequest {
     }
 }
 
-impl Default for CheckRunRequest {
-    fn default() -> Self {
-        Self::All
-    }
-}
-
 impl FromStr for CheckRunRequest {
     type Err = anyhow::Error;
 
@@ -44,19 +42,31 @@ impl FromStr for CheckRunRequest {
                 return Ok(Self::All);
             }
 
-            if let Ok(check) = AccountChecks::from_str(&without_whitespace) {
-                results.push(check.into());
-            } else if let Ok(check) = RepositoryChecks::from_str(&without_whitespace) {
-                results.push(check.into());
-            } else {
-                bail!("unknown check type")
-            }
+            results.push(without_whitespace.parse()?);
         }
 
         Ok(Self::Specific(results))
     }
 }
 
+impl<'de> Deserialize<'de> for CheckRunRequest {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Spec {
+            One(String),
+            Many(Vec<String>),
+        }
+
+        let spec = match Spec::deserialize(deserializer)? {
+            Spec::One(spec) => spec,
+            Spec::Many(specs) => specs.join(","),
+        };
+
+        spec.parse().map_err(D::Error::custom)
+    }
+}
+
 impl IntoIterator for CheckRunRequest {
     type Item = Checks;
 
@@ -68,6 +78,7 @@ impl IntoIterator for CheckRunRequest {
                 let mut result = vec![];
                 result.extend(RepositoryChecks::iter().map(Into::into));
                 result.extend(AccountChecks::iter().map(Into::into));
+                result.extend(OrganizationChecks::iter().map(Into::into));
                 result.into_iter()
             }
             CheckRunRequest::Specific(selected) => selected.into_iter(),
@@ -75,33 +86,79 @@ impl IntoIterator for CheckRunRequest {
     }
 }
 
+#[derive(Debug, Subcommand)]
+pub enum Command {
+    /// Manage the configuration file
+    #[command(subcommand)]
+    Config(ConfigCommand),
+}
+
+#[derive(Debug, Subcommand)]
+pub enum ConfigCommand {
+    /// Check that the configuration file is valid
+    Validate,
+}
+
 #[derive(Debug, Parser)]
+#[command(subcommand_negates_reqs = true)]
 pub struct Args {
+    #[command(subcommand)]
+    pub command: Option<Command>,
+
     /// GitHub Personal Access Token
-    #[arg(long, env = "GITHUB_TOKEN")]
-    pub github_token: SecureString,
+    #[arg(long, env = "GITHUB_TOKEN", required = true)]
+    pub github_token: Option<SecureString>,
+
+    /// Path to the configuration file. Defaults to `ghsec.toml` if it exists.
+    #[arg(short = 'c', long, env = "GHSEC_CONFIG")]
+    pub config: Option<PathBuf>,
 
     /// Should we fix things?
     #[arg(long)]
     pub fix: bool,
 
-    /// Which checks to run
-    #[arg(short = 'C', long, default_value = "all")]
-    pub checks: CheckRunRequest,
+    /// Allow `--fix` to delete stale deploy keys. Deleting a deploy key cannot be undone.
+    #[arg(long, requires = "fix")]
+    pub delete_stale_deploy_keys: bool,
+
+    /// Which checks to run. Defaults to all checks, unless set in the configuration file.
+    #[arg(short = 'C', long)]
+    pub checks: Option<CheckRunRequest>,
 
     /// Output logs as JSON
     #[arg(long)]
     pub json: bool,
 
+    /// Write findings as a SARIF log to the given file (`-` for stdout)
+    #[arg(long)]
+    pub sarif: Option<PathBuf>,
+
+    /// Only report findings that are absent from this baseline file
+    #[arg(long)]
+    pub baseline: Option<PathBuf>,
+
+    /// Write the current findings to a baseline file
+    #[arg(long)]
+    pub write_baseline: Option<PathBuf>,
+
+    /// Exit with a non-zero code if findings at or above this severity are reported
+    #[arg(long)]
+    pub fail_on: Option<Severity>,
+
     /// Enable debug logs
     #[arg(short = 'D', long)]
     pub debug: bool,
 
+    /// Organizations to run checks on, instead of the repositories owned by the authenticated user
+    #[arg(long = "org", value_name = "ORG")]
+    pub orgs: Vec<String>,
+
     /// Target repositories to run checks on. Supports globs.
     #[arg(default_value = "*")]
     pub repository_names: glob::Pattern,
 
-    /// Which secrets should trigger warnings instead of regular info
-    #[arg(long, default_value = "^GH_(TOKEN|PAT)$")]
-    pub repository_secrets_warn_secret_names: regex::Regex,
+    /// Which secrets should trigger warnings instead of regular info. Overrides the
+    /// `repository_secrets.warn_secret_names` option from the configuration file.
+    #[arg(long)]
+    pub repository_secrets_warn_secret_names: Option<regex::Regex>,
 }
@@ -0,0 +1,93 @@
+//! Baseline of known findings
+//!
+//! A baseline is a snapshot of the findings reported by a previous run. When a baseline is given,
+//! only findings that are absent from it are reported, so ghsec can be adopted on existing
+//! repositories without failing on every known issue. Findings are matched using their
+//! [fingerprint](crate::findings::Finding::fingerprint), which does not depend on the message.
+
+use std::{collections::HashSet, fs::File, io::BufReader, path::Path};
+
+use anyhow::{bail, Context};
+use serde::{Deserialize, Serialize};
+
+use crate::findings::Finding;
+
+/// Current version of the baseline file format
+const VERSION: u32 = 1;
+
+#[derive(Debug, Serialize, Deserialize)]
+struct BaselineEntry {
+    fingerprint: String,
+    #[serde(flatten)]
+    finding: Finding,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct BaselineFile {
+    version: u32,
+    findings: Vec<BaselineEntry>,
+}
+
+/// Set of known findings
+#[derive(Debug)]
+pub struct Baseline {
+    fingerprints: HashSet<String>,
+}
+
+impl Baseline {
+    /// Load a baseline from the given file
+    pub fn load(path: &Path) -> anyhow::Result<Self> {
+        let file = File::open(path)
+            .with_context(|| format!("failed to open baseline {}", path.display()))?;
+        let baseline: BaselineFile = serde_json::from_reader(BufReader::new(file))
+            .with_context(|| format!("invalid baseline {}", path.display()))?;
+
+        if baseline.version != VERSION {
+            bail!(
+                "unsupported baseline version {} in {}",
+                baseline.version,
+                path.display()
+            );
+        }
+
+        Ok(Self {
+            fingerprints: baseline
+                .findings
+                .into_iter()
+                .map(|entry| entry.fingerprint)
+                .collect(),
+        })
+    }
+
+    /// Write the given findings as a baseline file
+    pub fn write(path: &Path, findings: &[Finding]) -> anyhow::Result<()> {
+        let mut findings: Vec<_> = findings
+            .iter()
+            .map(|finding| BaselineEntry {
+                fingerprint: finding.fingerprint(),
+                finding: finding.clone(),
+            })
+            .collect();
+
+        // Sort entries so baselines can be diffed between runs
+        findings.sort_by(|a, b| a.fingerprint.cmp(&b.fingerprint));
+        findings.dedup_by(|a, b| a.fingerprint == b.fingerprint);
+
+        let file = File::create(path)
+            .with_context(|| format!("failed to create baseline {}", path.display()))?;
+        serde_json::to_writer_pretty(
+            file,
+            &BaselineFile {
+                version: VERSION,
+                findings,
+            },
+        )?;
+
+        Ok(())
+    }
+
+    /// `true` if the given finding is part of this baseline
+    pub fn contains(&self, finding: &Finding) -> bool {
+        self.fingerprints.contains(&finding.fingerprint())
+    }
+}
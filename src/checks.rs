@@ -1,8 +1,21 @@
 //! Implementation for security checks on repositories
 
+use std::{
+    collections::HashMap,
+    str::FromStr,
+    sync::{Arc, Mutex},
+};
+
+use anyhow::bail;
 use async_trait::async_trait;
+use chrono::Utc;
 use enum_dispatch::enum_dispatch;
-use octocrab::{models::Repository, Octocrab};
+use octocrab::{models::Repository, Octocrab, Page};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use tokio::sync::OnceCell;
+
+mod allowed_actions;
+pub use allowed_actions::*;
 
 mod branch_protections;
 pub use branch_protections::*;
@@ -10,16 +23,56 @@ pub use branch_protections::*;
 mod code_review_limits;
 pub use code_review_limits::*;
 
+mod code_scanning;
+pub use code_scanning::*;
+
+mod collaborators;
+pub use collaborators::*;
+
 mod default_worfklow_permissions;
 pub use default_worfklow_permissions::*;
 
+mod dependabot;
+pub use dependabot::*;
+
+mod deploy_keys;
+pub use deploy_keys::*;
+
 mod fork_pull_request_workflows;
 pub use fork_pull_request_workflows::*;
 
+mod member_privileges;
+pub use member_privileges::*;
+
+mod organization_secrets;
+pub use organization_secrets::*;
+
+mod repository_rulesets;
+pub use repository_rulesets::*;
+
 mod repository_secrets;
 pub use repository_secrets::*;
 
-use crate::Args;
+mod secret_scanning;
+pub use secret_scanning::*;
+
+mod self_hosted_runners;
+pub use self_hosted_runners::*;
+
+mod vulnerability_reporting;
+pub use vulnerability_reporting::*;
+
+mod webhooks;
+pub use webhooks::*;
+
+mod workflow_files;
+pub use workflow_files::*;
+
+use crate::{
+    config::{CheckOptions, Config},
+    findings::Finding,
+    Args,
+};
 
 /// Context for running a check against GitHub
 pub struct CheckCtx<'c> {
@@ -27,26 +80,256 @@ pub struct CheckCtx<'c> {
     pub args: &'c Args,
     /// GitHub API client
     pub gh: &'c Octocrab,
+    /// Configuration file
+    pub config: &'c Config,
+    /// Options for the checks, resolved for the current repository
+    pub options: CheckOptions,
+    /// Full name of the repository, or login of the organization checks are running on
+    pub target: Option<String>,
+    /// Responses shared by the checks of all targets
+    cache: Arc<ResponseCache>,
+}
+
+/// Responses to `GET` requests, fetched once per run
+#[derive(Default)]
+struct ResponseCache {
+    responses: Mutex<HashMap<String, Arc<OnceCell<serde_json::Value>>>>,
 }
 
 impl<'c> CheckCtx<'c> {
-    pub fn new(args: &'c Args, gh: &'c Octocrab) -> Self {
-        Self { args, gh }
+    pub fn new(args: &'c Args, gh: &'c Octocrab, config: &'c Config) -> anyhow::Result<Self> {
+        Ok(Self {
+            args,
+            gh,
+            config,
+            options: config.options(args)?,
+            target: None,
+            cache: Default::default(),
+        })
+    }
+
+    /// Create a context for running checks on the given repository
+    pub fn for_repository(&self, full_name: &str) -> anyhow::Result<Self> {
+        Ok(Self {
+            args: self.args,
+            gh: self.gh,
+            config: self.config,
+            options: self.config.options_for(self.args, full_name)?,
+            target: Some(full_name.to_owned()),
+            cache: self.cache.clone(),
+        })
+    }
+
+    /// Create a context for running checks on the given organization
+    pub fn for_organization(&self, organization: &str) -> anyhow::Result<Self> {
+        Ok(Self {
+            args: self.args,
+            gh: self.gh,
+            config: self.config,
+            options: self.config.options(self.args)?,
+            target: Some(organization.to_owned()),
+            cache: self.cache.clone(),
+        })
+    }
+
+    /// Send a `GET` request whose response is shared by the checks of all targets, such as the
+    /// settings of an organization owning several repositories. Failed requests are not cached.
+    pub async fn get_cached<T: DeserializeOwned>(&self, route: String) -> anyhow::Result<T> {
+        let cell = self
+            .cache
+            .responses
+            .lock()
+            .unwrap()
+            .entry(route.clone())
+            .or_default()
+            .clone();
+
+        let response = cell
+            .get_or_try_init(|| {
+                self.gh
+                    .get::<serde_json::Value, _, _>(route, Option::<()>::None.as_ref())
+            })
+            .await?;
+
+        Ok(serde_json::from_value(response.clone())?)
+    }
+
+    /// `true` if running with `--fix`, and findings of the given check and rule are not
+    /// suppressed for the current target
+    pub fn should_fix(&self, check: impl Into<Checks>, rule: &str) -> bool {
+        self.args.fix
+            && !crate::suppressions::is_suppressed(
+                &self.config.suppressions,
+                self.target.as_deref(),
+                &check.into().to_string(),
+                rule,
+                Utc::now().date_naive(),
+            )
+    }
+
+    /// `true` if running with `--fix`, and at least one of the given fixable findings of a check
+    /// is not suppressed for the current target
+    pub fn should_fix_any(&self, check: impl Into<Checks> + Clone, findings: &[Finding]) -> bool {
+        findings
+            .iter()
+            .any(|finding| finding.fixable && self.should_fix(check.clone(), &finding.rule))
+    }
+}
+
+/// Error of a check whose fixes failed. It keeps the findings of the check, so they are still
+/// reported while the run is marked as failed.
+#[derive(Debug)]
+pub struct FixError {
+    /// Findings reported by the check
+    pub findings: Vec<Finding>,
+    /// Errors of the fixes that failed
+    pub errors: Vec<anyhow::Error>,
+}
+
+impl std::fmt::Display for FixError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} fixes failed", self.errors.len())
+    }
+}
+
+impl std::error::Error for FixError {}
+
+/// Result of a check: its findings if none of its fixes failed, or a [`FixError`] keeping them
+pub(crate) fn fixed(
+    findings: Vec<Finding>,
+    errors: impl IntoIterator<Item = anyhow::Error>,
+) -> anyhow::Result<Vec<Finding>> {
+    let errors: Vec<_> = errors.into_iter().collect();
+
+    if errors.is_empty() {
+        Ok(findings)
+    } else {
+        Err(FixError { findings, errors }.into())
+    }
+}
+
+/// Send a `PUT` request to an endpoint that responds with `204 No Content`
+pub(crate) async fn put_no_content<B: Serialize + ?Sized>(
+    gh: &Octocrab,
+    route: impl AsRef<str>,
+    body: Option<&B>,
+) -> octocrab::Result<()> {
+    let response = gh._put(route.as_ref(), body).await?;
+    octocrab::map_github_error(response).await?;
+    Ok(())
+}
+
+/// Send a `DELETE` request to an endpoint that responds with `204 No Content`
+pub(crate) async fn delete_no_content(
+    gh: &Octocrab,
+    route: impl AsRef<str>,
+) -> octocrab::Result<()> {
+    let response = gh._delete(route.as_ref(), Option::<&()>::None).await?;
+    octocrab::map_github_error(response).await?;
+    Ok(())
+}
+
+/// Send a `GET` request to a paginated list endpoint, and collect the items of every page
+pub(crate) async fn get_all_pages<T: DeserializeOwned>(
+    gh: &Octocrab,
+    route: impl AsRef<str>,
+) -> octocrab::Result<Vec<T>> {
+    let page: Page<T> = gh.get(route.as_ref(), Option::<&()>::None).await?;
+    gh.all_pages(page).await
+}
+
+/// A page of the endpoints listing secrets
+#[derive(Debug, Deserialize)]
+struct SecretsPage<T> {
+    total_count: usize,
+    secrets: Vec<T>,
+}
+
+/// Send `GET` requests to an endpoint listing secrets, and collect the secrets of every page.
+/// Octocrab's [`Page`] does not support these endpoints, so pages are requested one by one.
+pub(crate) async fn get_all_secrets<T: DeserializeOwned>(
+    gh: &Octocrab,
+    route: impl AsRef<str>,
+) -> octocrab::Result<Vec<T>> {
+    let mut secrets = vec![];
+
+    for page in 1.. {
+        let mut response: SecretsPage<T> = gh
+            .get(
+                format!("{}?per_page=100&page={page}", route.as_ref()),
+                Option::<()>::None.as_ref(),
+            )
+            .await?;
+
+        let last = response.secrets.is_empty()
+            || secrets.len() + response.secrets.len() >= response.total_count;
+        secrets.append(&mut response.secrets);
+
+        if last {
+            break;
+        }
+    }
+
+    Ok(secrets)
+}
+
+/// `true` if the given error from the contents API means the requested file does not exist,
+/// including when the repository is empty
+pub(crate) fn is_missing_content(err: &octocrab::Error) -> bool {
+    matches!(
+        err,
+        octocrab::Error::GitHub { source, .. }
+            if source.message == "Not Found" || source.message == "This repository is empty."
+    )
+}
+
+/// `true` if the given repository is public
+pub(crate) fn is_public(repository: &Repository) -> bool {
+    match repository.visibility.as_deref() {
+        Some(visibility) => visibility == "public",
+        None => repository.private == Some(false),
     }
 }
 
+/// Send a `GET` request to an endpoint that responds with `204 No Content` when a feature is
+/// enabled, and `404 Not Found` when it is disabled
+pub(crate) async fn get_enabled(gh: &Octocrab, route: impl AsRef<str>) -> octocrab::Result<bool> {
+    let response = gh._get(route.as_ref()).await?;
+    if response.status().as_u16() == 404 {
+        return Ok(false);
+    }
+
+    octocrab::map_github_error(response).await?;
+    Ok(true)
+}
+
 /// Represents the possible operations for a repository check
 #[async_trait]
 #[enum_dispatch]
 pub trait RepositoryCheck {
-    async fn run<'c>(&self, ctx: &'c CheckCtx<'c>, repository: &Repository) -> anyhow::Result<()>;
+    async fn run<'c>(
+        &self,
+        ctx: &'c CheckCtx<'c>,
+        repository: &Repository,
+    ) -> anyhow::Result<Vec<Finding>>;
 }
 
 /// Represents the possible operations for an account check
 #[async_trait]
 #[enum_dispatch]
 pub trait AccountCheck {
-    async fn run<'c>(&self, ctx: &'c CheckCtx<'c>) -> anyhow::Result<()>;
+    async fn run<'c>(&self, ctx: &'c CheckCtx<'c>) -> anyhow::Result<Vec<Finding>>;
+}
+
+/// Represents the possible operations for an organization check
+#[async_trait]
+#[enum_dispatch]
+pub trait OrganizationCheck {
+    async fn run<'c>(
+        &self,
+        ctx: &'c CheckCtx<'c>,
+        organization: &str,
+    ) -> anyhow::Result<Vec<Finding>>;
 }
 
 /// Represents all the available checks on a repository
@@ -54,10 +337,21 @@ pub trait AccountCheck {
 #[derive(Debug, Clone, strum::EnumIter, strum::EnumString, strum::Display)]
 #[strum(serialize_all = "snake_case")]
 pub enum RepositoryChecks {
+    AllowedActions,
     BranchProtections,
+    CodeScanning,
+    Collaborators,
     DefaultWorkflowPermissions,
+    Dependabot,
+    DeployKeys,
     ForkPullRequestWorkflows,
+    RepositoryRulesets,
     RepositorySecrets,
+    SecretScanning,
+    SelfHostedRunners,
+    VulnerabilityReporting,
+    Webhooks,
+    WorkflowFiles,
 }
 
 /// Represents all the available checks on an account
@@ -68,11 +362,116 @@ pub enum AccountChecks {
     CodeReviewLimits,
 }
 
+/// Represents all the available checks on an organization
+#[enum_dispatch(OrganizationCheck)]
+#[derive(Debug, Clone, strum::EnumIter, strum::EnumString, strum::Display)]
+#[strum(serialize_all = "snake_case")]
+pub enum OrganizationChecks {
+    MemberPrivileges,
+    OrganizationDefaultWorkflowPermissions,
+    OrganizationSecrets,
+}
+
 /// Represents all the available checks
 #[derive(Debug, Clone)]
 pub enum Checks {
     Repository(RepositoryChecks),
     Account(AccountChecks),
+    Organization(OrganizationChecks),
+}
+
+impl Checks {
+    /// Name and documentation of the module implementing this check, extracted by `build.rs`
+    fn module(&self) -> (&'static str, &'static str) {
+        macro_rules! module {
+            ($name:literal) => {
+                (
+                    $name,
+                    include_str!(concat!(env!("OUT_DIR"), "/checks/", $name, ".md")),
+                )
+            };
+        }
+
+        match self {
+            Self::Repository(RepositoryChecks::AllowedActions(_)) => module!("allowed_actions"),
+            Self::Repository(RepositoryChecks::BranchProtections(_)) => {
+                module!("branch_protections")
+            }
+            Self::Repository(RepositoryChecks::CodeScanning(_)) => module!("code_scanning"),
+            Self::Repository(RepositoryChecks::Collaborators(_)) => module!("collaborators"),
+            Self::Repository(RepositoryChecks::DefaultWorkflowPermissions(_)) => {
+                module!("default_worfklow_permissions")
+            }
+            Self::Repository(RepositoryChecks::Dependabot(_)) => module!("dependabot"),
+            Self::Repository(RepositoryChecks::DeployKeys(_)) => module!("deploy_keys"),
+            Self::Repository(RepositoryChecks::ForkPullRequestWorkflows(_)) => {
+                module!("fork_pull_request_workflows")
+            }
+            Self::Repository(RepositoryChecks::RepositoryRulesets(_)) => {
+                module!("repository_rulesets")
+            }
+            Self::Repository(RepositoryChecks::RepositorySecrets(_)) => {
+                module!("repository_secrets")
+            }
+            Self::Repository(RepositoryChecks::SecretScanning(_)) => module!("secret_scanning"),
+            Self::Repository(RepositoryChecks::SelfHostedRunners(_)) => {
+                module!("self_hosted_runners")
+            }
+            Self::Repository(RepositoryChecks::VulnerabilityReporting(_)) => {
+                module!("vulnerability_reporting")
+            }
+            Self::Repository(RepositoryChecks::Webhooks(_)) => module!("webhooks"),
+            Self::Repository(RepositoryChecks::WorkflowFiles(_)) => module!("workflow_files"),
+            Self::Account(AccountChecks::CodeReviewLimits(_)) => module!("code_review_limits"),
+            Self::Organization(OrganizationChecks::MemberPrivileges(_)) => {
+                module!("member_privileges")
+            }
+            Self::Organization(OrganizationChecks::OrganizationDefaultWorkflowPermissions(_)) => {
+                module!("default_worfklow_permissions")
+            }
+            Self::Organization(OrganizationChecks::OrganizationSecrets(_)) => {
+                module!("organization_secrets")
+            }
+        }
+    }
+
+    /// Module documentation for this check, as Markdown
+    pub fn docs(&self) -> &'static str {
+        let (_, docs) = self.module();
+        docs
+    }
+
+    /// URL to the online documentation for this check
+    pub fn docs_url(&self) -> String {
+        let (name, _) = self.module();
+        format!("https://alixinne.github.io/ghsec/ghsec/checks/{name}/index.html")
+    }
+}
+
+impl std::fmt::Display for Checks {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Repository(check) => check.fmt(f),
+            Self::Account(check) => check.fmt(f),
+            Self::Organization(check) => check.fmt(f),
+        }
+    }
+}
+
+impl FromStr for Checks {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if let Ok(check) = AccountChecks::from_str(s) {
+            Ok(check.into())
+        } else if let Ok(check) = RepositoryChecks::from_str(s) {
+            Ok(check.into())
+        } else if let Ok(check) = OrganizationChecks::from_str(s) {
+            Ok(check.into())
+        } else {
+            bail!("unknown check type")
+        }
+    }
 }
 
 impl From<RepositoryChecks> for Checks {
@@ -86,3 +485,9 @@ impl From<AccountChecks> for Checks {
         Self::Account(value)
     }
 }
+
+impl From<OrganizationChecks> for Checks {
+    fn from(value: OrganizationChecks) -> Self {
+        Self::Organization(value)
+    }
+}
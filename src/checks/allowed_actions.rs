@@ -0,0 +1,200 @@
+//! The `allowed_actions` check ensures that repositories restrict the GitHub Actions and reusable
+//! workflows their workflows can use.
+//!
+//! By default, workflows can use any action or reusable workflow published on GitHub. A
+//! compromised or malicious action runs with the permissions and secrets of the workflow using it,
+//! so repositories should only allow actions created by GitHub, actions from verified creators
+//! of the Marketplace, and an explicit allowlist of other actions.
+//!
+//! This check reports:
+//! - `all_actions_allowed`: the repository allows all actions and reusable workflows.
+//! - `wildcard_pattern`: the repository allows selected actions, but one of the allowed patterns
+//!   matches actions from any owner, such as `*` or `*/*@*`.
+//!
+//! When running with `--fix`, repositories allowing all actions are restricted to selected
+//! actions, using the `github_owned_allowed`, `verified_allowed` and `patterns_allowed` options.
+//! Make sure the allowlist contains every third-party action used by the workflows of the
+//! repositories before running with `--fix`, otherwise they will fail.
+//!
+//! # Options
+//!
+//! ```toml
+//! [allowed_actions]
+//! # Allow actions created by GitHub
+//! github_owned_allowed = true
+//! # Allow actions from verified creators of the GitHub Marketplace
+//! verified_allowed = true
+//! # Other allowed actions and reusable workflows, such as "my-org/*" or "owner/action@v1"
+//! patterns_allowed = []
+//! ```
+//!
+//! # Sources
+//!
+//! - [GitHub Docs](https://docs.github.com/en/repositories/managing-your-repositorys-settings-and-features/enabling-features-for-your-repository/managing-github-actions-settings-for-a-repository#managing-github-actions-permissions-for-your-repository)
+//! - [GitHub REST API](https://docs.github.com/en/rest/actions/permissions?apiVersion=2022-11-28#get-github-actions-permissions-for-a-repository)
+
+use anyhow::anyhow;
+use async_trait::async_trait;
+use octocrab::models::Repository;
+use serde::{Deserialize, Serialize};
+use tracing::info;
+
+use super::{fixed, put_no_content, CheckCtx, RepositoryCheck, RepositoryChecks};
+use crate::findings::{Finding, Severity};
+
+#[derive(Debug, Serialize, Deserialize)]
+struct ActionsPermissions {
+    enabled: bool,
+    // all, local_only or selected
+    allowed_actions: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct SelectedActions {
+    github_owned_allowed: bool,
+    verified_allowed: bool,
+    patterns_allowed: Vec<String>,
+}
+
+/// Options for the `allowed_actions` check
+#[derive(Debug, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct AllowedActionsOptions {
+    /// Allow actions created by GitHub
+    pub github_owned_allowed: bool,
+    /// Allow actions from verified creators of the GitHub Marketplace
+    pub verified_allowed: bool,
+    /// Other allowed actions and reusable workflows
+    pub patterns_allowed: Vec<String>,
+}
+
+impl Default for AllowedActionsOptions {
+    fn default() -> Self {
+        Self {
+            github_owned_allowed: true,
+            verified_allowed: true,
+            patterns_allowed: vec![],
+        }
+    }
+}
+
+impl From<&AllowedActionsOptions> for SelectedActions {
+    fn from(options: &AllowedActionsOptions) -> Self {
+        Self {
+            github_owned_allowed: options.github_owned_allowed,
+            verified_allowed: options.verified_allowed,
+            patterns_allowed: options.patterns_allowed.clone(),
+        }
+    }
+}
+
+/// `true` if the given pattern allows actions from any owner
+fn is_wildcard(pattern: &str) -> bool {
+    pattern.split('/').next().is_some_and(|owner| owner == "*")
+}
+
+/// Implementation for the `allowed_actions` check
+#[derive(Default, Debug, Clone, Copy)]
+pub struct AllowedActions;
+
+#[async_trait]
+impl RepositoryCheck for AllowedActions {
+    #[tracing::instrument(name = "allowed_actions", level = "info", skip_all)]
+    async fn run<'c>(
+        &self,
+        ctx: &'c CheckCtx<'c>,
+        repository: &Repository,
+    ) -> anyhow::Result<Vec<Finding>> {
+        let full_name = repository
+            .full_name
+            .as_ref()
+            .ok_or_else(|| anyhow!("missing full_name"))?;
+        let route = format!("/repos/{full_name}/actions/permissions");
+        let link = format!("https://github.com/{full_name}/settings/actions");
+
+        let permissions: ActionsPermissions =
+            ctx.gh.get(&route, Option::<()>::None.as_ref()).await?;
+
+        let mut findings = vec![];
+        let mut fix_errors = vec![];
+
+        if !permissions.enabled {
+            return Ok(findings);
+        }
+
+        match permissions.allowed_actions.as_deref() {
+            Some("all") => {
+                findings.push(
+                    Finding::new(
+                        "all_actions_allowed",
+                        Severity::Warning,
+                        "all actions and reusable workflows are allowed",
+                    )
+                    .with_link(&link)
+                    .fixable(),
+                );
+
+                if ctx.should_fix(
+                    RepositoryChecks::AllowedActions(*self),
+                    "all_actions_allowed",
+                ) {
+                    info!("restricting allowed actions");
+
+                    let result = async {
+                        put_no_content(
+                            ctx.gh,
+                            &route,
+                            Some(&ActionsPermissions {
+                                enabled: true,
+                                allowed_actions: Some("selected".to_owned()),
+                            }),
+                        )
+                        .await?;
+
+                        put_no_content(
+                            ctx.gh,
+                            format!("{route}/selected-actions"),
+                            Some(&SelectedActions::from(&ctx.options.allowed_actions)),
+                        )
+                        .await
+                    }
+                    .await;
+
+                    if let Err(err) = result {
+                        fix_errors.push(err.into());
+                    }
+                }
+            }
+            Some("selected") => {
+                let selected: SelectedActions = ctx
+                    .gh
+                    .get(
+                        format!("{route}/selected-actions"),
+                        Option::<()>::None.as_ref(),
+                    )
+                    .await?;
+
+                for pattern in selected
+                    .patterns_allowed
+                    .iter()
+                    .filter(|pattern| is_wildcard(pattern))
+                {
+                    findings.push(
+                        Finding::new(
+                            "wildcard_pattern",
+                            Severity::Warning,
+                            format!(
+                                "allowed actions pattern {pattern} matches actions from any owner"
+                            ),
+                        )
+                        .with_subject(pattern)
+                        .with_link(&link),
+                    );
+                }
+            }
+            _ => {}
+        }
+
+        fixed(findings, fix_errors)
+    }
+}
@@ -10,8 +10,40 @@
 //!
 //! Due to limitations in the REST API, only branch protections with matching branches can be
 //! discovered and checked. For simplicity, this check currently only checks the default branch.
+//! If the default branch has no branch protection but its rulesets require pull requests or
+//! forbid force pushes, it is not reported as unprotected when the `repository_rulesets` check
+//! also runs on the repository, since that check evaluates the rulesets instead.
 //!
-//! When running with `--fix`, this check currently does not do anything.
+//! The expected settings are described by a template in the check options. Settings that are
+//! enabled in the template are required on the default branch, while force pushes and deletions
+//! are always expected to be forbidden.
+//!
+//! When running with `--fix`, a missing branch protection on the default branch is created from
+//! the template. For existing branch protections, only the settings drifting from the template are
+//! changed. Status checks, reviews and admin enforcement that are already enabled are updated
+//! through their own endpoints. Enabling status checks or reviews, and forbidding force pushes or
+//! deletions, requires replacing the whole branch protection: it is then replaced with its current
+//! settings, including push restrictions, where only the drifting settings are changed.
+//!
+//! # Options
+//!
+//! ```toml
+//! [branch_protections]
+//! # Minimum number of approving reviews required before merging a pull request
+//! required_approving_review_count = 1
+//! # Dismiss approving reviews when new commits are pushed
+//! dismiss_stale_reviews = true
+//! # Require an approving review from code owners
+//! require_code_owner_reviews = false
+//! # Require the most recent push to be approved by someone else than its author
+//! require_last_push_approval = false
+//! # Enforce the branch protection for administrators
+//! enforce_admins = true
+//! # Status checks that must pass before merging
+//! required_status_checks = []
+//! # Require branches to be up to date with the default branch before merging
+//! strict_status_checks = false
+//! ```
 //!
 //! # Sources
 //!
@@ -24,7 +56,8 @@ use octocrab::models::Repository;
 use serde::{Deserialize, Serialize};
 use tracing::{info, warn};
 
-use super::{CheckCtx, RepositoryCheck};
+use super::{branch_rules, fixed, CheckCtx, Checks, RepositoryCheck, RepositoryChecks};
+use crate::findings::{Finding, Severity};
 
 #[derive(Debug, Serialize, Deserialize)]
 struct BranchProtection {
@@ -38,12 +71,25 @@ struct BranchProtection {
     required_conversation_resolution: ProtectionFlag,
     lock_branch: ProtectionFlag,
     allow_fork_syncing: ProtectionFlag,
+    block_creations: Option<ProtectionFlag>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
 struct RequiredStatusChecks {
     contexts: Vec<String>,
+    #[serde(default)]
+    checks: Vec<StatusCheck>,
     enforcement_level: Option<String>,
+    #[serde(default)]
+    strict: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct StatusCheck {
+    context: String,
+    // Any app can set the status if missing
+    #[serde(skip_serializing_if = "Option::is_none")]
+    app_id: Option<i64>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -58,66 +104,629 @@ struct RequiredPullRequestReviews {
     require_code_owner_reviews: bool,
     required_approving_review_count: i32,
     require_last_push_approval: bool,
+    bypass_pull_request_allowances: Option<Restrictions>,
 }
 
 // TODO: Implement sub-models
 #[derive(Debug, Serialize, Deserialize)]
 struct Restrictions {
+    #[serde(default)]
     users: Vec<serde_json::Value>,
+    #[serde(default)]
     teams: Vec<serde_json::Value>,
+    #[serde(default)]
     apps: Vec<serde_json::Value>,
 }
 
-/// Implementation for the `repository_secrets` check
+/// Request body for the users, teams and apps of a restriction
+#[derive(Debug, Clone, Serialize)]
+struct RestrictionsUpdate {
+    users: Vec<String>,
+    teams: Vec<String>,
+    apps: Vec<String>,
+}
+
+impl From<&Restrictions> for RestrictionsUpdate {
+    fn from(restrictions: &Restrictions) -> Self {
+        fn names(values: &[serde_json::Value], key: &str) -> Vec<String> {
+            values
+                .iter()
+                .filter_map(|value| value.get(key)?.as_str().map(str::to_owned))
+                .collect()
+        }
+
+        Self {
+            users: names(&restrictions.users, "login"),
+            teams: names(&restrictions.teams, "slug"),
+            apps: names(&restrictions.apps, "slug"),
+        }
+    }
+}
+
+/// Request body for creating or replacing a branch protection
+#[derive(Debug, Serialize)]
+struct BranchProtectionUpdate {
+    required_status_checks: Option<RequiredStatusChecksUpdate>,
+    enforce_admins: bool,
+    required_pull_request_reviews: Option<RequiredPullRequestReviewsUpdate>,
+    // Push restrictions are not managed by ghsec, existing ones are kept
+    restrictions: Option<RestrictionsUpdate>,
+    required_linear_history: bool,
+    allow_force_pushes: bool,
+    allow_deletions: bool,
+    required_conversation_resolution: bool,
+    lock_branch: bool,
+    allow_fork_syncing: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    block_creations: Option<bool>,
+}
+
+/// Request body for updating the required status checks of a branch protection
+#[derive(Debug, Default, Clone, Serialize)]
+struct RequiredStatusChecksUpdate {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    strict: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    contexts: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    checks: Option<Vec<StatusCheck>>,
+}
+
+impl RequiredStatusChecksUpdate {
+    fn add_contexts(&mut self, contexts: &[String]) {
+        for context in contexts {
+            self.contexts
+                .get_or_insert_with(Vec::new)
+                .push(context.clone());
+
+            if let Some(checks) = &mut self.checks {
+                checks.push(StatusCheck {
+                    context: context.clone(),
+                    app_id: None,
+                });
+            }
+        }
+    }
+}
+
+impl From<&RequiredStatusChecks> for RequiredStatusChecksUpdate {
+    fn from(current: &RequiredStatusChecks) -> Self {
+        let mut checks = current.checks.clone();
+        for context in &current.contexts {
+            if !checks.iter().any(|check| check.context == *context) {
+                checks.push(StatusCheck {
+                    context: context.clone(),
+                    app_id: None,
+                });
+            }
+        }
+
+        Self {
+            strict: Some(current.strict),
+            contexts: Some(checks.iter().map(|check| check.context.clone()).collect()),
+            checks: Some(checks),
+        }
+    }
+}
+
+/// Request body for adding contexts to the required status checks of a branch protection
+#[derive(Debug, Serialize)]
+struct StatusCheckContexts {
+    contexts: Vec<String>,
+}
+
+/// Request body for updating the required reviews of a branch protection
+#[derive(Debug, Default, Clone, Serialize)]
+struct RequiredPullRequestReviewsUpdate {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    dismiss_stale_reviews: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    require_code_owner_reviews: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    required_approving_review_count: Option<i32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    require_last_push_approval: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    dismissal_restrictions: Option<RestrictionsUpdate>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    bypass_pull_request_allowances: Option<RestrictionsUpdate>,
+}
+
+impl RequiredPullRequestReviewsUpdate {
+    /// Override the settings of this update with the ones set in `fix`
+    fn merge(&mut self, fix: &Self) {
+        self.dismiss_stale_reviews = fix.dismiss_stale_reviews.or(self.dismiss_stale_reviews);
+        self.require_code_owner_reviews = fix
+            .require_code_owner_reviews
+            .or(self.require_code_owner_reviews);
+        self.required_approving_review_count = fix
+            .required_approving_review_count
+            .or(self.required_approving_review_count);
+        self.require_last_push_approval = fix
+            .require_last_push_approval
+            .or(self.require_last_push_approval);
+    }
+}
+
+impl From<&RequiredPullRequestReviews> for RequiredPullRequestReviewsUpdate {
+    fn from(current: &RequiredPullRequestReviews) -> Self {
+        Self {
+            dismiss_stale_reviews: Some(current.dismiss_stale_reviews),
+            require_code_owner_reviews: Some(current.require_code_owner_reviews),
+            required_approving_review_count: Some(current.required_approving_review_count),
+            require_last_push_approval: Some(current.require_last_push_approval),
+            dismissal_restrictions: current.dismissal_restrictions.as_ref().map(Into::into),
+            bypass_pull_request_allowances: current
+                .bypass_pull_request_allowances
+                .as_ref()
+                .map(Into::into),
+        }
+    }
+}
+
+impl From<&BranchProtectionsOptions> for RequiredStatusChecksUpdate {
+    fn from(options: &BranchProtectionsOptions) -> Self {
+        Self {
+            strict: Some(options.strict_status_checks),
+            contexts: Some(options.required_status_checks.clone()),
+            checks: None,
+        }
+    }
+}
+
+impl From<&BranchProtectionsOptions> for RequiredPullRequestReviewsUpdate {
+    fn from(options: &BranchProtectionsOptions) -> Self {
+        Self {
+            dismiss_stale_reviews: Some(options.dismiss_stale_reviews),
+            require_code_owner_reviews: Some(options.require_code_owner_reviews),
+            required_approving_review_count: Some(options.required_approving_review_count),
+            require_last_push_approval: Some(options.require_last_push_approval),
+            ..Default::default()
+        }
+    }
+}
+
+impl From<&BranchProtectionsOptions> for BranchProtectionUpdate {
+    fn from(options: &BranchProtectionsOptions) -> Self {
+        Self {
+            required_status_checks: (!options.required_status_checks.is_empty())
+                .then(|| options.into()),
+            enforce_admins: options.enforce_admins,
+            required_pull_request_reviews: Some(options.into()),
+            restrictions: None,
+            required_linear_history: false,
+            allow_force_pushes: false,
+            allow_deletions: false,
+            required_conversation_resolution: false,
+            lock_branch: false,
+            allow_fork_syncing: false,
+            block_creations: None,
+        }
+    }
+}
+
+/// Changes to an existing branch protection. Each setting is updated through its own endpoint when
+/// possible, so the settings ghsec does not manage are kept as they are.
+#[derive(Debug, Default)]
+struct ProtectionFixes {
+    required_status_checks: Option<RequiredStatusChecksUpdate>,
+    status_check_contexts: Vec<String>,
+    enforce_admins: bool,
+    required_pull_request_reviews: Option<RequiredPullRequestReviewsUpdate>,
+    forbid_force_pushes: bool,
+    forbid_deletions: bool,
+}
+
+impl ProtectionFixes {
+    fn is_empty(&self) -> bool {
+        self.required_status_checks.is_none()
+            && self.status_check_contexts.is_empty()
+            && !self.enforce_admins
+            && self.required_pull_request_reviews.is_none()
+            && !self.forbid_force_pushes
+            && !self.forbid_deletions
+    }
+
+    /// `true` if these changes can only be applied by replacing the whole branch protection, since
+    /// the endpoints for each setting only update settings that are already enabled
+    fn needs_replace(&self, protection: &BranchProtection) -> bool {
+        self.forbid_force_pushes
+            || self.forbid_deletions
+            || (self.required_status_checks.is_some() || !self.status_check_contexts.is_empty())
+                && protection.required_status_checks.is_none()
+            || self.required_pull_request_reviews.is_some()
+                && protection.required_pull_request_reviews.is_none()
+    }
+
+    /// Request body replacing `protection` with these changes applied
+    fn replace(&self, protection: &BranchProtection) -> BranchProtectionUpdate {
+        let required_status_checks = match &protection.required_status_checks {
+            Some(current) => {
+                let mut update = RequiredStatusChecksUpdate::from(current);
+                if let Some(strict) = self
+                    .required_status_checks
+                    .as_ref()
+                    .and_then(|fix| fix.strict)
+                {
+                    update.strict = Some(strict);
+                }
+                update.add_contexts(&self.status_check_contexts);
+                Some(update)
+            }
+            None => self.required_status_checks.clone(),
+        };
+
+        let required_pull_request_reviews = match &protection.required_pull_request_reviews {
+            Some(current) => {
+                let mut update = RequiredPullRequestReviewsUpdate::from(current);
+                if let Some(fix) = &self.required_pull_request_reviews {
+                    update.merge(fix);
+                }
+                Some(update)
+            }
+            None => self.required_pull_request_reviews.clone(),
+        };
+
+        BranchProtectionUpdate {
+            required_status_checks,
+            enforce_admins: protection.enforce_admins.enabled || self.enforce_admins,
+            required_pull_request_reviews,
+            restrictions: protection.restrictions.as_ref().map(Into::into),
+            required_linear_history: protection.required_linear_history.enabled,
+            allow_force_pushes: protection.allow_force_pushes.enabled && !self.forbid_force_pushes,
+            allow_deletions: protection.allow_deletions.enabled && !self.forbid_deletions,
+            required_conversation_resolution: protection.required_conversation_resolution.enabled,
+            lock_branch: protection.lock_branch.enabled,
+            allow_fork_syncing: protection.allow_fork_syncing.enabled,
+            block_creations: protection.block_creations.as_ref().map(|flag| flag.enabled),
+        }
+    }
+
+    /// Apply these changes to the branch protection `protection` at `route`
+    async fn apply(
+        &self,
+        ctx: &CheckCtx<'_>,
+        route: &str,
+        protection: &BranchProtection,
+    ) -> octocrab::Result<()> {
+        if self.needs_replace(protection) {
+            ctx.gh
+                .put::<serde_json::Value, _, _>(route, Some(&self.replace(protection)))
+                .await?;
+            return Ok(());
+        }
+
+        if let Some(update) = &self.required_status_checks {
+            ctx.gh
+                .patch::<serde_json::Value, _, _>(
+                    format!("{route}/required_status_checks"),
+                    Some(update),
+                )
+                .await?;
+        }
+
+        if !self.status_check_contexts.is_empty() {
+            ctx.gh
+                .post::<_, serde_json::Value>(
+                    format!("{route}/required_status_checks/contexts"),
+                    Some(&StatusCheckContexts {
+                        contexts: self.status_check_contexts.clone(),
+                    }),
+                )
+                .await?;
+        }
+
+        if self.enforce_admins {
+            ctx.gh
+                .post::<_, serde_json::Value>(
+                    format!("{route}/enforce_admins"),
+                    Option::<&()>::None,
+                )
+                .await?;
+        }
+
+        if let Some(update) = &self.required_pull_request_reviews {
+            ctx.gh
+                .patch::<serde_json::Value, _, _>(
+                    format!("{route}/required_pull_request_reviews"),
+                    Some(update),
+                )
+                .await?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Options for the `branch_protections` check, used as a template for the expected protection
+#[derive(Debug, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct BranchProtectionsOptions {
+    /// Minimum number of approving reviews required before merging a pull request
+    pub required_approving_review_count: i32,
+    /// Dismiss approving reviews when new commits are pushed
+    pub dismiss_stale_reviews: bool,
+    /// Require an approving review from code owners
+    pub require_code_owner_reviews: bool,
+    /// Require the most recent push to be approved by someone else than its author
+    pub require_last_push_approval: bool,
+    /// Enforce the branch protection for administrators
+    pub enforce_admins: bool,
+    /// Status checks that must pass before merging
+    pub required_status_checks: Vec<String>,
+    /// Require branches to be up to date with the default branch before merging
+    pub strict_status_checks: bool,
+}
+
+impl Default for BranchProtectionsOptions {
+    fn default() -> Self {
+        Self {
+            required_approving_review_count: 1,
+            dismiss_stale_reviews: true,
+            require_code_owner_reviews: false,
+            require_last_push_approval: false,
+            enforce_admins: true,
+            required_status_checks: vec![],
+            strict_status_checks: false,
+        }
+    }
+}
+
+/// Compare an existing branch protection to the template, reporting drifting settings and
+/// recording in `fixes` the changes for the rules `can_fix` allows fixing
+fn check_protection(
+    protection: &BranchProtection,
+    options: &BranchProtectionsOptions,
+    can_fix: impl Fn(&str) -> bool,
+    fixes: &mut ProtectionFixes,
+) -> Vec<Finding> {
+    let mut findings = vec![];
+
+    match &protection.required_status_checks {
+        Some(required_status_checks) => {
+            let missing: Vec<_> = options
+                .required_status_checks
+                .iter()
+                .filter(|context| !required_status_checks.contexts.contains(context))
+                .cloned()
+                .collect();
+
+            if !missing.is_empty() {
+                findings.push(
+                    Finding::new(
+                        "required_status_checks",
+                        Severity::Warning,
+                        format!("missing required status checks: {}", missing.join(", ")),
+                    )
+                    .fixable(),
+                );
+
+                if can_fix("required_status_checks") {
+                    fixes.status_check_contexts = missing;
+                }
+            } else if required_status_checks.contexts.is_empty() {
+                findings.push(Finding::new(
+                    "required_status_checks",
+                    Severity::Warning,
+                    "no contexts configured for required status checks",
+                ));
+            }
+
+            if options.strict_status_checks && !required_status_checks.strict {
+                findings.push(
+                    Finding::new(
+                        "strict_status_checks",
+                        Severity::Warning,
+                        "branch protection does not require branches to be up to date",
+                    )
+                    .fixable(),
+                );
+
+                if can_fix("strict_status_checks") {
+                    fixes.required_status_checks = Some(RequiredStatusChecksUpdate {
+                        strict: Some(true),
+                        ..Default::default()
+                    });
+                }
+            }
+        }
+        None => {
+            let mut finding = Finding::new(
+                "required_status_checks",
+                Severity::Warning,
+                "no required checks configured for branch protection",
+            );
+
+            if !options.required_status_checks.is_empty() {
+                finding = finding.fixable();
+
+                if can_fix("required_status_checks") {
+                    fixes.required_status_checks = Some(options.into());
+                }
+            }
+
+            findings.push(finding);
+        }
+    }
+
+    if options.enforce_admins && !protection.enforce_admins.enabled {
+        findings.push(
+            Finding::new(
+                "enforce_admins",
+                Severity::Warning,
+                "branch protection not enforced for admins",
+            )
+            .fixable(),
+        );
+        fixes.enforce_admins = can_fix("enforce_admins");
+    }
+
+    match &protection.required_pull_request_reviews {
+        Some(reviews) => {
+            let mut update = RequiredPullRequestReviewsUpdate::default();
+
+            if reviews.required_approving_review_count < options.required_approving_review_count {
+                findings.push(
+                    Finding::new(
+                        "required_approving_review_count",
+                        Severity::Warning,
+                        format!(
+                            "branch protection requires {} approving reviews, expected at least {}",
+                            reviews.required_approving_review_count,
+                            options.required_approving_review_count
+                        ),
+                    )
+                    .fixable(),
+                );
+
+                if can_fix("required_approving_review_count") {
+                    update.required_approving_review_count =
+                        Some(options.required_approving_review_count);
+                }
+            }
+
+            if options.dismiss_stale_reviews && !reviews.dismiss_stale_reviews {
+                findings.push(
+                    Finding::new(
+                        "dismiss_stale_reviews",
+                        Severity::Warning,
+                        "branch protection does not dismiss stale reviews",
+                    )
+                    .fixable(),
+                );
+
+                if can_fix("dismiss_stale_reviews") {
+                    update.dismiss_stale_reviews = Some(true);
+                }
+            }
+
+            if options.require_code_owner_reviews && !reviews.require_code_owner_reviews {
+                findings.push(
+                    Finding::new(
+                        "require_code_owner_reviews",
+                        Severity::Warning,
+                        "branch protection does not require code owner reviews",
+                    )
+                    .fixable(),
+                );
+
+                if can_fix("require_code_owner_reviews") {
+                    update.require_code_owner_reviews = Some(true);
+                }
+            }
+
+            if options.require_last_push_approval && !reviews.require_last_push_approval {
+                findings.push(
+                    Finding::new(
+                        "require_last_push_approval",
+                        Severity::Warning,
+                        "branch protection does not require approval of the most recent push",
+                    )
+                    .fixable(),
+                );
+
+                if can_fix("require_last_push_approval") {
+                    update.require_last_push_approval = Some(true);
+                }
+            }
+
+            if update.dismiss_stale_reviews.is_some()
+                || update.require_code_owner_reviews.is_some()
+                || update.required_approving_review_count.is_some()
+                || update.require_last_push_approval.is_some()
+            {
+                fixes.required_pull_request_reviews = Some(update);
+            }
+        }
+        None => {
+            findings.push(
+                Finding::new(
+                    "required_pull_request_reviews",
+                    Severity::Warning,
+                    "branch protection does not require pull requests",
+                )
+                .fixable(),
+            );
+
+            if can_fix("required_pull_request_reviews") {
+                fixes.required_pull_request_reviews = Some(options.into());
+            }
+        }
+    }
+
+    if protection.allow_force_pushes.enabled {
+        findings.push(
+            Finding::new(
+                "allow_force_pushes",
+                Severity::Warning,
+                "branch protection allows force pushes",
+            )
+            .fixable(),
+        );
+        fixes.forbid_force_pushes = can_fix("allow_force_pushes");
+    }
+
+    if protection.allow_deletions.enabled {
+        findings.push(
+            Finding::new(
+                "allow_deletions",
+                Severity::Warning,
+                "branch protection allows deletions",
+            )
+            .fixable(),
+        );
+        fixes.forbid_deletions = can_fix("allow_deletions");
+    }
+
+    findings
+}
+
+/// Implementation for the `branch_protections` check
 #[derive(Default, Debug, Clone, Copy)]
 pub struct BranchProtections;
 
 #[async_trait]
 impl RepositoryCheck for BranchProtections {
     #[tracing::instrument(name = "branch_protections", level = "info", skip_all)]
-    async fn run<'c>(&self, ctx: &'c CheckCtx<'c>, repository: &Repository) -> anyhow::Result<()> {
+    async fn run<'c>(
+        &self,
+        ctx: &'c CheckCtx<'c>,
+        repository: &Repository,
+    ) -> anyhow::Result<Vec<Finding>> {
+        let mut findings = vec![];
+        let mut fix_errors = vec![];
+
         if let Some(default_branch) = &repository.default_branch {
             let full_name = repository
                 .full_name
                 .as_ref()
                 .ok_or_else(|| anyhow!("missing repository full name"))?;
+            let link = format!("https://github.com/{full_name}/settings/branches");
+            let route = format!("/repos/{full_name}/branches/{default_branch}/protection");
+            let options = &ctx.options.branch_protections;
 
             let protection = ctx
                 .gh
-                .get::<BranchProtection, _, _>(
-                    format!("/repos/{full_name}/branches/{default_branch}/protection"),
-                    Option::<()>::None.as_ref(),
-                )
+                .get::<BranchProtection, _, _>(&route, Option::<()>::None.as_ref())
                 .await;
 
+            let check = RepositoryChecks::BranchProtections(*self);
+
             match protection {
                 Ok(protection) => {
-                    if let Some(required_status_checks) = &protection.required_status_checks {
-                        if required_status_checks.contexts.is_empty() {
-                            warn!("no contexts configured for required status checks");
-                        }
-                    } else {
-                        warn!("no required checks configured for branch protection");
-                    }
-
-                    if !protection.enforce_admins.enabled {
-                        warn!("branch protection not enforced for admins");
-                    }
-
-                    if protection.required_pull_request_reviews.is_none() {
-                        warn!("branch protection does not require pull requests");
-                    }
-
-                    if protection.allow_force_pushes.enabled {
-                        warn!("branch protection allows force pushes");
-                    }
-
-                    if protection.allow_deletions.enabled {
-                        warn!("branch protection allows deletions");
-                    }
+                    let mut fixes = ProtectionFixes::default();
+                    findings = check_protection(
+                        &protection,
+                        options,
+                        |rule| ctx.should_fix(check.clone(), rule),
+                        &mut fixes,
+                    );
 
-                    if ctx.args.fix {
-                        info!("nothing to fix regarding branch protections yet");
+                    if !fixes.is_empty() {
+                        info!("fixing branch protection on {default_branch}");
+                        if let Err(err) = fixes.apply(ctx, &route, &protection).await {
+                            fix_errors.push(err.into());
+                        }
                     }
                 }
                 Err(err) => {
@@ -125,16 +734,65 @@ impl RepositoryCheck for BranchProtections {
 
                     if let octocrab::Error::GitHub { source, .. } = &err {
                         if source.message == "Branch not protected" {
-                            // TODO: Allow auto-fixing this
-                            let link = format!("https://github.com/{full_name}/settings/branches");
-                            warn!(link, "missing branch protection on default branch, you should configure one at {link}");
                             handled = true;
 
-                            if ctx.args.fix {
-                                info!("nothing to fix regarding branch protections yet");
+                            // Rulesets protecting the branch are checked by the
+                            // `repository_rulesets` check, if it runs on this repository
+                            let rulesets_checked = ctx
+                                .config
+                                .checks_for(ctx.args, full_name)
+                                .into_iter()
+                                .any(|check| {
+                                    matches!(
+                                        check,
+                                        Checks::Repository(RepositoryChecks::RepositoryRulesets(_))
+                                    )
+                                });
+
+                            let protected_by_rulesets = rulesets_checked
+                                && branch_rules(ctx, full_name, default_branch)
+                                    .await?
+                                    .iter()
+                                    .any(|rule| {
+                                        matches!(
+                                            rule.rule_type.as_str(),
+                                            "pull_request" | "non_fast_forward"
+                                        )
+                                    });
+
+                            if protected_by_rulesets {
+                                info!("{default_branch} is protected by rulesets");
+                            } else {
+                                findings.push(
+                                Finding::new(
+                                    "missing_branch_protection",
+                                    Severity::Warning,
+                                    format!("missing branch protection on default branch, you should configure one at {link}"),
+                                )
+                                .fixable(),
+                            );
+
+                                if ctx.should_fix(check, "missing_branch_protection") {
+                                    info!("creating branch protection on {default_branch}");
+
+                                    if let Err(err) = ctx
+                                        .gh
+                                        .put::<serde_json::Value, _, _>(
+                                            &route,
+                                            Some(&BranchProtectionUpdate::from(options)),
+                                        )
+                                        .await
+                                    {
+                                        fix_errors.push(err.into());
+                                    }
+                                }
                             }
                         } else if source.message.contains("Upgrade to GitHub Pro") {
-                            warn!("feature not available for this repository type");
+                            findings.push(Finding::new(
+                                "unavailable",
+                                Severity::Warning,
+                                "feature not available for this repository type",
+                            ));
                             handled = true;
                         }
                     }
@@ -144,10 +802,109 @@ impl RepositoryCheck for BranchProtections {
                     }
                 }
             }
+
+            findings = findings
+                .into_iter()
+                .map(|finding| finding.with_subject(default_branch).with_link(&link))
+                .collect();
         } else {
             warn!("missing default branch information, cannot check branch protections for it");
         }
 
-        Ok(())
+        fixed(findings, fix_errors)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn flag(enabled: bool) -> serde_json::Value {
+        serde_json::json!({ "enabled": enabled })
+    }
+
+    fn protection(reviews: serde_json::Value) -> BranchProtection {
+        serde_json::from_value(serde_json::json!({
+            "required_status_checks": {
+                "contexts": ["ci"],
+                "checks": [{ "context": "ci", "app_id": 15368 }],
+                "strict": true,
+            },
+            "enforce_admins": flag(false),
+            "required_pull_request_reviews": reviews,
+            "restrictions": {
+                "users": [{ "login": "octocat" }],
+                "teams": [{ "slug": "admins" }],
+                "apps": [],
+            },
+            "required_linear_history": flag(true),
+            "allow_force_pushes": flag(true),
+            "allow_deletions": flag(false),
+            "required_conversation_resolution": flag(true),
+            "lock_branch": flag(false),
+            "allow_fork_syncing": flag(false),
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn per_setting_fixes() {
+        let protection = protection(serde_json::json!({
+            "dismiss_stale_reviews": false,
+            "require_code_owner_reviews": false,
+            "required_approving_review_count": 1,
+            "require_last_push_approval": false,
+        }));
+
+        let fixes = ProtectionFixes {
+            enforce_admins: true,
+            required_pull_request_reviews: Some(RequiredPullRequestReviewsUpdate {
+                dismiss_stale_reviews: Some(true),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+
+        assert!(!fixes.needs_replace(&protection));
+    }
+
+    #[test]
+    fn replace_keeps_current_settings() {
+        let protection = protection(serde_json::Value::Null);
+        let options = BranchProtectionsOptions::default();
+
+        let mut fixes = ProtectionFixes::default();
+        check_protection(&protection, &options, |_| true, &mut fixes);
+        assert!(fixes.needs_replace(&protection));
+
+        let update = serde_json::to_value(fixes.replace(&protection)).unwrap();
+        assert_eq!(
+            update,
+            serde_json::json!({
+                "required_status_checks": {
+                    "strict": true,
+                    "contexts": ["ci"],
+                    "checks": [{ "context": "ci", "app_id": 15368 }],
+                },
+                "enforce_admins": true,
+                "required_pull_request_reviews": {
+                    "dismiss_stale_reviews": true,
+                    "require_code_owner_reviews": false,
+                    "required_approving_review_count": 1,
+                    "require_last_push_approval": false,
+                },
+                "restrictions": {
+                    "users": ["octocat"],
+                    "teams": ["admins"],
+                    "apps": [],
+                },
+                "required_linear_history": true,
+                "allow_force_pushes": false,
+                "allow_deletions": false,
+                "required_conversation_resolution": true,
+                "lock_branch": false,
+                "allow_fork_syncing": false,
+            })
+        );
     }
 }
@@ -6,9 +6,9 @@
 //! could enable a pull request to be merged, as long as it was created by another user or tool,
 //! and set up for auto-merge in one way or another.
 //!
-//! This check will print an error with a link which can be followed to enable _Code review limits_
-//! on the target repository, or globally for the account. Since apparently this "internal hackaton
-//! project" (see the
+//! This check will report a reminder, as an `info` finding, with a link which can be followed to
+//! enable _Code review limits_ on the target repository, or globally for the account. Since
+//! apparently this "internal hackaton project" (see the
 //! [announcement](https://docs.github.com/en/repositories/managing-your-repositorys-settings-and-features/managing-repository-settings/managing-pull-request-reviews-in-your-repository))
 //! got pushed to production without thinking about adding an API, there is no way to automate this
 //! without horrible hacks for now.
@@ -19,9 +19,9 @@
 //! - [Blog Announcement](https://docs.github.com/en/repositories/managing-your-repositorys-settings-and-features/managing-repository-settings/managing-pull-request-reviews-in-your-repository)
 
 use async_trait::async_trait;
-use tracing::error;
 
 use super::{AccountCheck, CheckCtx};
+use crate::findings::{Finding, Severity};
 
 /// Implementation for the `code_review_limits` check
 #[derive(Default, Debug, Clone, Copy)]
@@ -30,9 +30,13 @@ pub struct CodeReviewLimits;
 #[async_trait]
 impl AccountCheck for CodeReviewLimits {
     #[tracing::instrument(name = "code_review_limits", level = "info", skip_all)]
-    async fn run<'c>(&self, _ctx: &'c CheckCtx<'c>) -> anyhow::Result<()> {
+    async fn run<'c>(&self, _ctx: &'c CheckCtx<'c>) -> anyhow::Result<Vec<Finding>> {
         let link = "https://github.com/settings/code_review_limits";
-        error!(link, "ghsec cannot programatically check or change settings for Code Review Limits. Go to {link} and make sure that the option is enabled.");
-        Ok(())
+        Ok(vec![Finding::new(
+            "manual_check",
+            Severity::Info,
+            format!("ghsec cannot programatically check or change settings for Code Review Limits. Go to {link} and make sure that the option is enabled."),
+        )
+        .with_link(link)])
     }
 }
@@ -0,0 +1,216 @@
+//! The `code_scanning` check ensures that CodeQL code scanning is configured on repositories
+//! written in languages CodeQL supports.
+//!
+//! Code scanning analyzes the code of a repository on every push and pull request, and reports
+//! security vulnerabilities and coding errors as alerts. The _default setup_ configures CodeQL
+//! without adding a workflow to the repository. It is free for public repositories, and requires
+//! GitHub Advanced Security (or GitHub Code Security) for private repositories.
+//!
+//! This check reports a `default_setup` finding for repositories where code scanning default setup
+//! is not configured, and which contain code in at least one language supported by CodeQL: C/C++,
+//! C#, Go, Java/Kotlin, JavaScript/TypeScript, Python, Ruby and Swift. Repositories that cannot
+//! use code scanning with their current plan are reported as an `unavailable` information.
+//!
+//! Repositories using an advanced setup are not reported: they either have CodeQL analyses that
+//! were not uploaded by the default setup, or a workflow on their default branch using the
+//! `github/codeql-action` actions.
+//!
+//! When running with `--fix`, this will enable code scanning default setup, which starts a first
+//! analysis of the repository.
+//!
+//! # Sources
+//!
+//! - [GitHub Docs](https://docs.github.com/en/code-security/code-scanning/enabling-code-scanning/configuring-default-setup-for-code-scanning)
+//! - [GitHub REST API](https://docs.github.com/en/rest/code-scanning/code-scanning?apiVersion=2022-11-28#get-a-code-scanning-default-setup-configuration)
+
+use std::collections::{BTreeSet, HashMap};
+
+use anyhow::anyhow;
+use async_trait::async_trait;
+use octocrab::models::Repository;
+use serde::{Deserialize, Serialize};
+use tracing::{debug, info};
+
+use super::{fetch_workflows, fixed, CheckCtx, RepositoryCheck, RepositoryChecks, Workflow};
+use crate::findings::{Finding, Severity};
+
+/// CodeQL languages, by name of the GitHub language they analyze
+const CODEQL_LANGUAGES: &[(&str, &str)] = &[
+    ("C", "c-cpp"),
+    ("C++", "c-cpp"),
+    ("C#", "csharp"),
+    ("Go", "go"),
+    ("Java", "java-kotlin"),
+    ("Kotlin", "java-kotlin"),
+    ("JavaScript", "javascript-typescript"),
+    ("TypeScript", "javascript-typescript"),
+    ("Python", "python"),
+    ("Ruby", "ruby"),
+    ("Swift", "swift"),
+];
+
+#[derive(Debug, Serialize, Deserialize)]
+struct DefaultSetup {
+    // configured or not-configured
+    state: String,
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    languages: Vec<String>,
+}
+
+/// Prefix of the actions used by CodeQL advanced setup workflows
+const CODEQL_ACTION: &str = "github/codeql-action/";
+
+#[derive(Debug, Serialize, Deserialize)]
+struct Analysis {
+    id: u64,
+}
+
+/// `true` if the repository uses CodeQL advanced setup, with CodeQL analyses or a workflow running
+/// the CodeQL action
+async fn has_advanced_setup(
+    ctx: &CheckCtx<'_>,
+    full_name: &str,
+    default_branch: Option<&str>,
+) -> anyhow::Result<bool> {
+    match ctx
+        .gh
+        .get::<Vec<Analysis>, _, _>(
+            format!("/repos/{full_name}/code-scanning/analyses?tool_name=CodeQL&per_page=1"),
+            Option::<()>::None.as_ref(),
+        )
+        .await
+    {
+        Ok(analyses) if !analyses.is_empty() => return Ok(true),
+        Ok(_) => {}
+        // Repositories without analyses respond with a 404 error
+        Err(octocrab::Error::GitHub { source, .. })
+            if source.message == "Not Found" || source.message.contains("no analysis found") => {}
+        Err(err) => return Err(err.into()),
+    }
+
+    let Some(default_branch) = default_branch else {
+        return Ok(false);
+    };
+
+    for file in fetch_workflows(ctx, full_name, default_branch).await? {
+        // Invalid workflows are reported by the `workflow_files` check
+        let Ok(workflow) = file.parse() else {
+            continue;
+        };
+
+        let uses_codeql = workflow.jobs().any(|(_, job)| {
+            Workflow::steps(job).any(|(_, step)| {
+                step.get("uses")
+                    .and_then(|uses| uses.as_str())
+                    .is_some_and(|uses| uses.starts_with(CODEQL_ACTION))
+            })
+        });
+
+        if uses_codeql {
+            return Ok(true);
+        }
+    }
+
+    Ok(false)
+}
+
+/// Implementation for the `code_scanning` check
+#[derive(Default, Debug, Clone, Copy)]
+pub struct CodeScanning;
+
+#[async_trait]
+impl RepositoryCheck for CodeScanning {
+    #[tracing::instrument(name = "code_scanning", level = "info", skip_all)]
+    async fn run<'c>(
+        &self,
+        ctx: &'c CheckCtx<'c>,
+        repository: &Repository,
+    ) -> anyhow::Result<Vec<Finding>> {
+        let full_name = repository
+            .full_name
+            .as_ref()
+            .ok_or_else(|| anyhow!("missing full_name"))?;
+        let route = format!("/repos/{full_name}/code-scanning/default-setup");
+        let link = format!("https://github.com/{full_name}/settings/security_analysis");
+
+        let languages: HashMap<String, i64> = ctx
+            .gh
+            .get(
+                format!("/repos/{full_name}/languages"),
+                Option::<()>::None.as_ref(),
+            )
+            .await?;
+
+        let supported: BTreeSet<_> = CODEQL_LANGUAGES
+            .iter()
+            .filter(|(language, _)| languages.contains_key(*language))
+            .map(|(_, codeql)| *codeql)
+            .collect();
+
+        if supported.is_empty() {
+            return Ok(vec![]);
+        }
+
+        let setup = match ctx
+            .gh
+            .get::<DefaultSetup, _, _>(&route, Option::<()>::None.as_ref())
+            .await
+        {
+            Ok(setup) => setup,
+            Err(octocrab::Error::GitHub { source, .. })
+                if source.message.contains("Advanced Security")
+                    || source.message.contains("not enabled") =>
+            {
+                return Ok(vec![Finding::new(
+                    "unavailable",
+                    Severity::Info,
+                    format!("code scanning is not available: {}", source.message),
+                )
+                .with_link(link)]);
+            }
+            Err(err) => return Err(err.into()),
+        };
+
+        let mut findings = vec![];
+        let mut fix_errors = vec![];
+
+        if setup.state != "configured"
+            && has_advanced_setup(ctx, full_name, repository.default_branch.as_deref()).await?
+        {
+            debug!("code scanning uses an advanced setup");
+        } else if setup.state != "configured" {
+            findings.push(
+                Finding::new(
+                    "default_setup",
+                    Severity::Warning,
+                    format!(
+                        "code scanning is not configured, CodeQL supports {}",
+                        supported.iter().copied().collect::<Vec<_>>().join(", ")
+                    ),
+                )
+                .with_link(&link)
+                .fixable(),
+            );
+
+            if ctx.should_fix(RepositoryChecks::CodeScanning(*self), "default_setup") {
+                info!("enabling code scanning default setup");
+
+                if let Err(err) = ctx
+                    .gh
+                    .patch::<serde_json::Value, _, _>(
+                        route,
+                        Some(&DefaultSetup {
+                            state: "configured".to_owned(),
+                            languages: vec![],
+                        }),
+                    )
+                    .await
+                {
+                    fix_errors.push(err.into());
+                }
+            }
+        }
+
+        fixed(findings, fix_errors)
+    }
+}
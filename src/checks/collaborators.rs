@@ -0,0 +1,278 @@
+//! The `collaborators` check audits the direct collaborators and pending invitations of
+//! repositories.
+//!
+//! Collaborators are rarely removed once they stopped working on a repository, especially on
+//! repositories owned by a personal account, where the owner is the only one who can see them.
+//! This check reports:
+//! - `collaborator`: every direct collaborator of the repository and their permission, as
+//!   information.
+//! - `privileged_outside_collaborator`: a collaborator who is not a member of the organization
+//!   owning the repository has the `admin` or `maintain` permission. On repositories owned by a
+//!   personal account, every collaborator is an outside collaborator.
+//! - `permission_not_allowed`: a collaborator has a broader permission than allowed by the
+//!   `allowed_permissions` allowlist, or by `max_permission` for collaborators not listed in it.
+//! - `stale_invitation`: an invitation to collaborate is expired, or was sent more than
+//!   `max_invitation_age_days` days ago.
+//!
+//! Permissions are compared in this order: `read`, `triage`, `write`, `maintain`, `admin`.
+//! Collaborators listed in `allowed_permissions` are not reported as privileged outside
+//! collaborators.
+//!
+//! When running with `--fix`, this will cancel stale invitations. Collaborator permissions have to
+//! be reviewed manually.
+//!
+//! # Options
+//!
+//! ```toml
+//! [collaborators]
+//! # Broadest permission of collaborators not listed in `allowed_permissions`
+//! max_permission = "write"
+//! # Broadest permission of specific collaborators, by login
+//! allowed_permissions = { octocat = "admin" }
+//! # Age in days after which pending invitations are considered stale
+//! max_invitation_age_days = 7
+//! ```
+//!
+//! # Sources
+//!
+//! - [GitHub Docs](https://docs.github.com/en/account-and-profile/setting-up-and-managing-your-personal-account-on-github/managing-access-to-your-personal-repositories/inviting-collaborators-to-a-personal-repository)
+//! - [GitHub REST API](https://docs.github.com/en/rest/collaborators/collaborators?apiVersion=2022-11-28#list-repository-collaborators)
+//! - [GitHub REST API (invitations)](https://docs.github.com/en/rest/collaborators/invitations?apiVersion=2022-11-28#list-repository-invitations)
+
+use std::collections::{HashMap, HashSet};
+
+use anyhow::anyhow;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use octocrab::models::Repository;
+use serde::{Deserialize, Serialize};
+use tracing::info;
+
+use super::{delete_no_content, fixed, get_all_pages, CheckCtx, RepositoryCheck, RepositoryChecks};
+use crate::findings::{Finding, Severity};
+
+/// Repository permission, from the narrowest to the broadest
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize, strum::Display,
+)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum Permission {
+    Read,
+    Triage,
+    Write,
+    Maintain,
+    Admin,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct Collaborator {
+    login: String,
+    permissions: CollaboratorPermissions,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct CollaboratorPermissions {
+    pull: bool,
+    #[serde(default)]
+    triage: bool,
+    push: bool,
+    #[serde(default)]
+    maintain: bool,
+    admin: bool,
+}
+
+impl CollaboratorPermissions {
+    /// Broadest permission granted, if any
+    fn permission(&self) -> Option<Permission> {
+        [
+            (self.admin, Permission::Admin),
+            (self.maintain, Permission::Maintain),
+            (self.push, Permission::Write),
+            (self.triage, Permission::Triage),
+            (self.pull, Permission::Read),
+        ]
+        .into_iter()
+        .find_map(|(granted, permission)| granted.then_some(permission))
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct Invitation {
+    id: u64,
+    invitee: Option<Invitee>,
+    permissions: Permission,
+    created_at: DateTime<Utc>,
+    #[serde(default)]
+    expired: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct Invitee {
+    login: String,
+}
+
+/// Options for the `collaborators` check
+#[derive(Debug, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct CollaboratorsOptions {
+    /// Broadest permission of collaborators not listed in `allowed_permissions`
+    pub max_permission: Permission,
+    /// Broadest permission of specific collaborators, by login
+    pub allowed_permissions: HashMap<String, Permission>,
+    /// Age in days after which pending invitations are considered stale
+    pub max_invitation_age_days: i64,
+}
+
+impl Default for CollaboratorsOptions {
+    fn default() -> Self {
+        Self {
+            max_permission: Permission::Write,
+            allowed_permissions: HashMap::new(),
+            max_invitation_age_days: 7,
+        }
+    }
+}
+
+/// Implementation for the `collaborators` check
+#[derive(Default, Debug, Clone, Copy)]
+pub struct Collaborators;
+
+#[async_trait]
+impl RepositoryCheck for Collaborators {
+    #[tracing::instrument(name = "collaborators", level = "info", skip_all)]
+    async fn run<'c>(
+        &self,
+        ctx: &'c CheckCtx<'c>,
+        repository: &Repository,
+    ) -> anyhow::Result<Vec<Finding>> {
+        let owner = repository
+            .owner
+            .as_ref()
+            .ok_or_else(|| anyhow!("missing owner"))?;
+        let full_name = format!("{}/{}", owner.login, repository.name);
+        let link = format!("https://github.com/{full_name}/settings/access");
+        let options = &ctx.options.collaborators;
+
+        let collaborators: Vec<Collaborator> = get_all_pages(
+            ctx.gh,
+            format!("/repos/{full_name}/collaborators?affiliation=direct&per_page=100"),
+        )
+        .await?;
+
+        // On personal repositories, every collaborator besides the owner is an outside collaborator
+        let outside: Option<HashSet<String>> = if owner.r#type == "Organization" {
+            let outside: Vec<Collaborator> = get_all_pages(
+                ctx.gh,
+                format!("/repos/{full_name}/collaborators?affiliation=outside&per_page=100"),
+            )
+            .await?;
+
+            Some(outside.into_iter().map(|user| user.login).collect())
+        } else {
+            None
+        };
+
+        let mut findings = vec![];
+        let mut fix_errors = vec![];
+
+        for collaborator in &collaborators {
+            let login = &collaborator.login;
+            if *login == owner.login {
+                continue;
+            }
+
+            let Some(permission) = collaborator.permissions.permission() else {
+                continue;
+            };
+
+            findings.push(
+                Finding::new(
+                    "collaborator",
+                    Severity::Info,
+                    format!("{login} is a collaborator with {permission} permission"),
+                )
+                .with_subject(login)
+                .with_link(&link),
+            );
+
+            let allowed = options.allowed_permissions.get(login);
+            let is_outside = outside
+                .as_ref()
+                .is_none_or(|outside| outside.contains(login));
+
+            if is_outside && permission >= Permission::Maintain && allowed.is_none() {
+                findings.push(
+                    Finding::new(
+                        "privileged_outside_collaborator",
+                        Severity::Warning,
+                        format!("outside collaborator {login} has {permission} permission"),
+                    )
+                    .with_subject(login)
+                    .with_link(&link),
+                );
+            } else if permission > *allowed.unwrap_or(&options.max_permission) {
+                findings.push(
+                    Finding::new(
+                        "permission_not_allowed",
+                        Severity::Warning,
+                        format!(
+                            "{login} has {permission} permission, only {} is allowed",
+                            allowed.unwrap_or(&options.max_permission)
+                        ),
+                    )
+                    .with_subject(login)
+                    .with_link(&link),
+                );
+            }
+        }
+
+        let invitations: Vec<Invitation> = get_all_pages(
+            ctx.gh,
+            format!("/repos/{full_name}/invitations?per_page=100"),
+        )
+        .await?;
+
+        let now = Utc::now();
+
+        for invitation in &invitations {
+            let invitee = invitation
+                .invitee
+                .as_ref()
+                .map_or("<unknown>", |invitee| invitee.login.as_str());
+            let age = (now - invitation.created_at).num_days();
+
+            if !invitation.expired && age <= options.max_invitation_age_days {
+                continue;
+            }
+
+            findings.push(
+                Finding::new(
+                    "stale_invitation",
+                    Severity::Warning,
+                    format!(
+                        "invitation of {invitee} with {} permission was sent {age} days ago",
+                        invitation.permissions
+                    ),
+                )
+                .with_subject(invitee)
+                .with_link(&link)
+                .fixable(),
+            );
+
+            if ctx.should_fix(RepositoryChecks::Collaborators(*self), "stale_invitation") {
+                info!("cancelling invitation of {invitee}");
+                if let Err(err) = delete_no_content(
+                    ctx.gh,
+                    format!("/repos/{full_name}/invitations/{}", invitation.id),
+                )
+                .await
+                {
+                    fix_errors.push(err.into());
+                }
+            }
+        }
+
+        fixed(findings, fix_errors)
+    }
+}
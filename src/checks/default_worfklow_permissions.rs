@@ -3,27 +3,46 @@
 //!
 //! There are two settings for _Default Workflow Permissions_:
 //! - `default_workflow_permissions`: can be `read` or `write`. Defaults to `write`. This controls
-//! whether the default `$GITHUB_TOKEN` provided to workflows has write access to the repository
-//! (contents, pull requests, etc.) or only read access. Setting this to `read` forces workflow
-//! authors to explicitly enable write access for various scopes, which reduces the attack surface
-//! of workflows pushed to the repository.
+//!   whether the default `$GITHUB_TOKEN` provided to workflows has write access to the repository
+//!   (contents, pull requests, etc.) or only read access. Setting this to `read` forces workflow
+//!   authors to explicitly enable write access for various scopes, which reduces the attack surface
+//!   of workflows pushed to the repository.
 //! - `can_approve_pull_request_reviews`: can be `true` or `false`. Defaults to `true`. This
-//! controls whether workflows (through the principal represented by the default `$GITHUB_TOKEN`)
-//! is allowed to approve pull requests. Since approving pull requests through a workflow is a way
-//! of circumventing branch protections, setting this to `false` prevents attacks that rely on this
-//! behavior.
+//!   controls whether workflows (through the principal represented by the default `$GITHUB_TOKEN`)
+//!   is allowed to approve pull requests. Since approving pull requests through a workflow is a way
+//!   of circumventing branch protections, setting this to `false` prevents attacks that rely on this
+//!   behavior.
 //!
-//! This check will emit warnings for repository where any of these settings do not use a secure
-//! value.
+//! This check will report a finding for repositories where any of these settings do not use a
+//! secure value. Workflows that do not declare their own `permissions`, and therefore depend on
+//! these settings, are reported by the `workflow_files` check.
 //!
 //! When running with `--fix`, this will set both these settings to secure values:
 //! - `default_workflow_permissions`: `read`
 //! - `can_approve_pull_request_reviews`: `false`
 //!
+//! # Organizations
+//!
+//! The same settings exist at the organization level, and are checked and fixed by the
+//! `organization_default_workflow_permissions` check when running with `--org`. Secure values set
+//! on an organization are forced on all its repositories, so fixing the organization is preferred
+//! over fixing each repository.
+//!
+//! For repositories owned by an organization, the repository check reports when an insecure value
+//! is the same as the organization default, in which case fixing the organization is preferred.
+//! The REST API does not tell whether a repository overrides the organization default, so such
+//! values are not reported as inherited. The organization settings are only fetched once per
+//! organization.
+//!
+//! The REST API does not tell either whether the organization or its enterprise prevents changing
+//! these settings on repositories. This is only known when trying to change them, so running with
+//! `--fix` reports a `forced_by_organization` finding instead of failing in that case.
+//!
 //! # Sources
 //!
 //! - [GitHub Docs](https://docs.github.com/en/repositories/managing-your-repositorys-settings-and-features/enabling-features-for-your-repository/managing-github-actions-settings-for-a-repository#setting-the-permissions-of-the-github_token-for-your-repository)
 //! - [GitHub REST API](https://docs.github.com/en/rest/actions/permissions?apiVersion=2022-11-28#get-default-workflow-permissions-for-a-repository)
+//! - [GitHub REST API (organizations)](https://docs.github.com/en/rest/actions/permissions?apiVersion=2022-11-28#get-default-workflow-permissions-for-an-organization)
 
 use anyhow::anyhow;
 use async_trait::async_trait;
@@ -31,15 +50,91 @@ use octocrab::models::Repository;
 use serde::{Deserialize, Serialize};
 use tracing::{info, warn};
 
-use super::{CheckCtx, RepositoryCheck};
+use super::{
+    fixed, put_no_content, CheckCtx, Checks, OrganizationCheck, OrganizationChecks,
+    RepositoryCheck, RepositoryChecks,
+};
+use crate::findings::{Finding, Severity};
 
 #[derive(Debug, Serialize, Deserialize)]
-struct DefaultRepositoryWorkflowPermissions {
+struct DefaultWorkflowPermissionsSettings {
     // read or write
     default_workflow_permissions: String,
     can_approve_pull_request_reviews: bool,
 }
 
+impl DefaultWorkflowPermissionsSettings {
+    /// Secure values for these settings, except for settings whose findings are suppressed
+    fn secure(&self, ctx: &CheckCtx<'_>, check: impl Into<Checks>) -> Self {
+        let check = check.into();
+
+        Self {
+            default_workflow_permissions: if ctx
+                .should_fix(check.clone(), "default_workflow_permissions")
+            {
+                "read".to_owned()
+            } else {
+                self.default_workflow_permissions.clone()
+            },
+            can_approve_pull_request_reviews: self.can_approve_pull_request_reviews
+                && !ctx.should_fix(check, "can_approve_pull_request_reviews"),
+        }
+    }
+
+    /// Report findings for settings that do not use secure values.
+    ///
+    /// If `organization` is set, insecure settings that have the same value in its settings are
+    /// reported as the same as the organization default.
+    fn findings(&self, organization: Option<(&str, &Self)>) -> Vec<Finding> {
+        let mut findings = vec![];
+
+        let suffix = |same_as_organization: bool| match organization {
+            Some((organization, _)) if same_as_organization => {
+                format!(" (same as the {organization} organization default)")
+            }
+            _ => String::new(),
+        };
+
+        if self.can_approve_pull_request_reviews {
+            let same_as_organization =
+                organization.is_some_and(|(_, settings)| settings.can_approve_pull_request_reviews);
+
+            findings.push(
+                Finding::new(
+                    "can_approve_pull_request_reviews",
+                    Severity::Warning,
+                    format!(
+                        "can_approve_pull_request_reviews is set to true{}",
+                        suffix(same_as_organization)
+                    ),
+                )
+                .fixable(),
+            );
+        }
+
+        if self.default_workflow_permissions != "read" {
+            let same_as_organization = organization.is_some_and(|(_, settings)| {
+                settings.default_workflow_permissions == self.default_workflow_permissions
+            });
+
+            findings.push(
+                Finding::new(
+                    "default_workflow_permissions",
+                    Severity::Warning,
+                    format!(
+                        "default_workflow_permissions is set to {}{}",
+                        self.default_workflow_permissions,
+                        suffix(same_as_organization)
+                    ),
+                )
+                .fixable(),
+            );
+        }
+
+        findings
+    }
+}
+
 /// Implementation for the `default_workflow_permissions` check
 #[derive(Default, Debug, Clone, Copy)]
 pub struct DefaultWorkflowPermissions;
@@ -47,47 +142,122 @@ pub struct DefaultWorkflowPermissions;
 #[async_trait]
 impl RepositoryCheck for DefaultWorkflowPermissions {
     #[tracing::instrument(name = "default_workflow_permissions", level = "info", skip_all)]
-    async fn run<'c>(&self, ctx: &'c CheckCtx<'c>, repository: &Repository) -> anyhow::Result<()> {
-        let route = format!(
-            "/repos/{}/actions/permissions/workflow",
-            repository
-                .full_name
-                .as_ref()
-                .ok_or_else(|| anyhow!("missing full_name"))?
-        );
-
-        let mut fix_needed = false;
-
-        let permissions: DefaultRepositoryWorkflowPermissions =
+    async fn run<'c>(
+        &self,
+        ctx: &'c CheckCtx<'c>,
+        repository: &Repository,
+    ) -> anyhow::Result<Vec<Finding>> {
+        let full_name = repository
+            .full_name
+            .as_ref()
+            .ok_or_else(|| anyhow!("missing full_name"))?;
+        let route = format!("/repos/{full_name}/actions/permissions/workflow");
+        let link = format!("https://github.com/{full_name}/settings/actions");
+
+        let permissions: DefaultWorkflowPermissionsSettings =
             ctx.gh.get(&route, Option::<()>::None.as_ref()).await?;
 
-        if permissions.can_approve_pull_request_reviews {
-            warn!("can_approve_pull_request_reviews is set to true");
-            fix_needed = true;
-        }
+        // Repositories owned by an organization default to its settings
+        let organization = repository
+            .owner
+            .as_ref()
+            .filter(|owner| owner.r#type == "Organization")
+            .map(|owner| owner.login.as_str());
 
-        if permissions.default_workflow_permissions != "read" {
-            warn!(
-                "default_workflow_permissions is set to {}",
-                permissions.default_workflow_permissions
-            );
-            fix_needed = true;
+        let mut organization_permissions = None;
+        if let Some(organization) = organization {
+            if permissions.can_approve_pull_request_reviews
+                || permissions.default_workflow_permissions != "read"
+            {
+                organization_permissions = ctx
+                    .get_cached::<DefaultWorkflowPermissionsSettings>(format!(
+                        "/orgs/{organization}/actions/permissions/workflow"
+                    ))
+                    .await
+                    .inspect_err(|err| warn!(?err, "failed to get organization settings"))
+                    .ok();
+            }
         }
 
-        if ctx.args.fix && fix_needed {
+        let mut findings: Vec<_> = permissions
+            .findings(organization.zip(organization_permissions.as_ref()))
+            .into_iter()
+            .map(|finding| finding.with_link(&link))
+            .collect();
+
+        let check = RepositoryChecks::DefaultWorkflowPermissions(*self);
+        let mut fix_errors = vec![];
+        if ctx.should_fix_any(check.clone(), &findings) {
             info!("fixing default workflow permissions");
 
-            ctx.gh
-                .put(
-                    route,
-                    Some(&DefaultRepositoryWorkflowPermissions {
-                        default_workflow_permissions: "read".to_owned(),
-                        can_approve_pull_request_reviews: false,
-                    }),
-                )
-                .await?;
+            let result = put_no_content(ctx.gh, route, Some(&permissions.secure(ctx, check))).await;
+
+            match (result, organization) {
+                (Ok(()), _) => {}
+                // The organization may prevent changing this setting on repositories, which is
+                // reported as a conflict
+                (Err(octocrab::Error::GitHub { source, .. }), Some(organization))
+                    if source.message == "Conflict" || source.message.contains("organization") =>
+                {
+                    findings.push(
+                        Finding::new(
+                            "forced_by_organization",
+                            Severity::Warning,
+                            format!("cannot fix default workflow permissions, they may be managed by the {organization} organization: {}", source.message),
+                        )
+                        .with_link(format!(
+                            "https://github.com/organizations/{organization}/settings/actions"
+                        )),
+                    );
+                }
+                (Err(err), _) => fix_errors.push(err.into()),
+            }
+        }
+
+        fixed(findings, fix_errors)
+    }
+}
+
+/// Implementation for the `organization_default_workflow_permissions` check
+#[derive(Default, Debug, Clone, Copy)]
+pub struct OrganizationDefaultWorkflowPermissions;
+
+#[async_trait]
+impl OrganizationCheck for OrganizationDefaultWorkflowPermissions {
+    #[tracing::instrument(
+        name = "organization_default_workflow_permissions",
+        level = "info",
+        skip_all
+    )]
+    async fn run<'c>(
+        &self,
+        ctx: &'c CheckCtx<'c>,
+        organization: &str,
+    ) -> anyhow::Result<Vec<Finding>> {
+        let route = format!("/orgs/{organization}/actions/permissions/workflow");
+        let link = format!("https://github.com/organizations/{organization}/settings/actions");
+
+        let permissions: DefaultWorkflowPermissionsSettings =
+            ctx.gh.get(&route, Option::<()>::None.as_ref()).await?;
+
+        let findings: Vec<_> = permissions
+            .findings(None)
+            .into_iter()
+            .map(|finding| finding.with_link(&link))
+            .collect();
+
+        let check = OrganizationChecks::OrganizationDefaultWorkflowPermissions(*self);
+        let mut fix_errors = vec![];
+        if ctx.should_fix_any(check.clone(), &findings) {
+            info!("fixing organization default workflow permissions");
+
+            if let Err(err) =
+                put_no_content(ctx.gh, route, Some(&permissions.secure(ctx, check))).await
+            {
+                fix_errors.push(err.into());
+            }
         }
 
-        Ok(())
+        fixed(findings, fix_errors)
     }
 }
@@ -0,0 +1,199 @@
+//! The `dependabot` check ensures that Dependabot alerts and security updates are enabled on
+//! repositories.
+//!
+//! Dependabot monitors the dependencies of a repository for known vulnerabilities:
+//! - `vulnerability_alerts`: Dependabot alerts report dependencies affected by a published
+//!   security advisory.
+//! - `automated_security_fixes`: Dependabot security updates open pull requests upgrading
+//!   vulnerable dependencies to a patched version. They require Dependabot alerts.
+//!
+//! Both features are free for all repositories, but are not always enabled by default.
+//!
+//! When the `summarize_alerts` option is set, this check also reports the number of open critical
+//! and high severity alerts of each repository, as an `open_alerts` finding.
+//!
+//! When running with `--fix`, this will enable Dependabot alerts and security updates.
+//!
+//! # Options
+//!
+//! ```toml
+//! [dependabot]
+//! # Report the number of open critical and high severity alerts
+//! summarize_alerts = false
+//! ```
+//!
+//! # Sources
+//!
+//! - [GitHub Docs](https://docs.github.com/en/code-security/dependabot/dependabot-alerts/about-dependabot-alerts)
+//! - [GitHub REST API](https://docs.github.com/en/rest/repos/repos?apiVersion=2022-11-28#check-if-vulnerability-alerts-are-enabled-for-a-repository)
+//! - [GitHub REST API (alerts)](https://docs.github.com/en/rest/dependabot/alerts?apiVersion=2022-11-28#list-dependabot-alerts-for-a-repository)
+
+use anyhow::anyhow;
+use async_trait::async_trait;
+use octocrab::models::Repository;
+use serde::{Deserialize, Serialize};
+use tracing::info;
+
+use super::{fixed, get_enabled, put_no_content, CheckCtx, RepositoryCheck, RepositoryChecks};
+use crate::findings::{Finding, Severity};
+
+/// Maximum number of alerts fetched when summarizing alerts
+const ALERTS_PER_PAGE: usize = 100;
+
+#[derive(Debug, Serialize, Deserialize)]
+struct AutomatedSecurityFixes {
+    enabled: bool,
+    paused: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct DependabotAlert {
+    number: i64,
+    security_advisory: SecurityAdvisory,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct SecurityAdvisory {
+    // low, medium, high or critical
+    severity: String,
+}
+
+/// Options for the `dependabot` check
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct DependabotOptions {
+    /// Report the number of open critical and high severity alerts
+    pub summarize_alerts: bool,
+}
+
+/// Implementation for the `dependabot` check
+#[derive(Default, Debug, Clone, Copy)]
+pub struct Dependabot;
+
+#[async_trait]
+impl RepositoryCheck for Dependabot {
+    #[tracing::instrument(name = "dependabot", level = "info", skip_all)]
+    async fn run<'c>(
+        &self,
+        ctx: &'c CheckCtx<'c>,
+        repository: &Repository,
+    ) -> anyhow::Result<Vec<Finding>> {
+        let full_name = repository
+            .full_name
+            .as_ref()
+            .ok_or_else(|| anyhow!("missing full_name"))?;
+        let link = format!("https://github.com/{full_name}/settings/security_analysis");
+
+        let alerts_route = format!("/repos/{full_name}/vulnerability-alerts");
+        let fixes_route = format!("/repos/{full_name}/automated-security-fixes");
+
+        let alerts_enabled = get_enabled(ctx.gh, &alerts_route).await?;
+        let fixes_enabled = match ctx
+            .gh
+            .get::<AutomatedSecurityFixes, _, _>(&fixes_route, Option::<()>::None.as_ref())
+            .await
+        {
+            Ok(fixes) => fixes.enabled,
+            Err(octocrab::Error::GitHub { source, .. }) if source.message == "Not Found" => false,
+            Err(err) => return Err(err.into()),
+        };
+
+        let mut findings = vec![];
+        let mut fix_errors = vec![];
+
+        if !alerts_enabled {
+            findings.push(
+                Finding::new(
+                    "vulnerability_alerts",
+                    Severity::Warning,
+                    "Dependabot alerts are disabled",
+                )
+                .with_link(&link)
+                .fixable(),
+            );
+        }
+
+        if !fixes_enabled {
+            findings.push(
+                Finding::new(
+                    "automated_security_fixes",
+                    Severity::Warning,
+                    "Dependabot security updates are disabled",
+                )
+                .with_link(&link)
+                .fixable(),
+            );
+        }
+
+        let mut alerts_fixed = alerts_enabled;
+        if !alerts_enabled
+            && ctx.should_fix(RepositoryChecks::Dependabot(*self), "vulnerability_alerts")
+        {
+            info!("enabling Dependabot alerts");
+            match put_no_content(ctx.gh, &alerts_route, Option::<&()>::None).await {
+                Ok(()) => alerts_fixed = true,
+                Err(err) => fix_errors.push(err.into()),
+            }
+        }
+
+        // Security updates require alerts to be enabled first
+        if !fixes_enabled
+            && alerts_fixed
+            && ctx.should_fix(
+                RepositoryChecks::Dependabot(*self),
+                "automated_security_fixes",
+            )
+        {
+            info!("enabling Dependabot security updates");
+            if let Err(err) = put_no_content(ctx.gh, &fixes_route, Option::<&()>::None).await {
+                fix_errors.push(err.into());
+            }
+        }
+
+        if alerts_enabled && ctx.options.dependabot.summarize_alerts {
+            let alerts: Vec<DependabotAlert> = ctx
+                .gh
+                .get(
+                    format!(
+                        "/repos/{full_name}/dependabot/alerts?state=open&severity=critical,high&per_page={ALERTS_PER_PAGE}"
+                    ),
+                    Option::<()>::None.as_ref(),
+                )
+                .await?;
+
+            let critical = alerts
+                .iter()
+                .filter(|alert| alert.security_advisory.severity == "critical")
+                .count();
+            let high = alerts.len() - critical;
+
+            if !alerts.is_empty() {
+                let at_least = if alerts.len() == ALERTS_PER_PAGE {
+                    "at least "
+                } else {
+                    ""
+                };
+
+                findings.push(
+                    Finding::new(
+                        "open_alerts",
+                        if critical > 0 {
+                            Severity::Error
+                        } else {
+                            Severity::Warning
+                        },
+                        format!(
+                            "{at_least}{} open Dependabot alerts: {critical} critical, {high} high",
+                            alerts.len()
+                        ),
+                    )
+                    .with_link(format!(
+                        "https://github.com/{full_name}/security/dependabot"
+                    )),
+                );
+            }
+        }
+
+        fixed(findings, fix_errors)
+    }
+}
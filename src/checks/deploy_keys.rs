@@ -0,0 +1,198 @@
+//! The `deploy_keys` check audits the SSH deploy keys of repositories.
+//!
+//! Deploy keys grant access to a single repository, and are often left behind on servers and CI
+//! systems long after they stopped being needed. This check reports:
+//! - `write_access`: the deploy key can push to the repository, bypassing the review process
+//!   unless the branches are protected.
+//! - `old_key`: the deploy key was created more than `max_age_days` days ago, and should be
+//!   rotated.
+//! - `unused_key`: the deploy key was last used more than `max_unused_days` days ago, or was never
+//!   used and was created more than `max_unused_days` days ago.
+//!
+//! When running with both `--fix` and `--delete-stale-deploy-keys`, keys reported by one of the
+//! rules listed in the `delete` option are deleted. By default, only unused keys are deleted.
+//! Deleting a deploy key cannot be undone, so it has to be confirmed explicitly on the command
+//! line. Keys used in the last `max_unused_days` days, and keys whose findings are suppressed, are
+//! never deleted, even if `delete` includes `old_key`.
+//!
+//! # Options
+//!
+//! ```toml
+//! [deploy_keys]
+//! # Age in days after which deploy keys should be rotated
+//! max_age_days = 365
+//! # Number of days after which deploy keys are considered unused
+//! max_unused_days = 90
+//! # Rules whose keys are deleted with `--fix --delete-stale-deploy-keys`: `old_key`, `unused_key`
+//! delete = ["unused_key"]
+//! ```
+//!
+//! # Sources
+//!
+//! - [GitHub Docs](https://docs.github.com/en/authentication/connecting-to-github-with-ssh/managing-deploy-keys#deploy-keys)
+//! - [GitHub REST API](https://docs.github.com/en/rest/deploy-keys/deploy-keys?apiVersion=2022-11-28#list-deploy-keys)
+
+use anyhow::anyhow;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use octocrab::models::Repository;
+use serde::{Deserialize, Serialize};
+use tracing::info;
+
+use super::{delete_no_content, fixed, get_all_pages, CheckCtx, RepositoryCheck, RepositoryChecks};
+use crate::findings::{Finding, Severity};
+
+#[derive(Debug, Serialize, Deserialize)]
+struct DeployKey {
+    id: u64,
+    title: String,
+    read_only: bool,
+    created_at: DateTime<Utc>,
+    last_used: Option<DateTime<Utc>>,
+}
+
+/// Rule reporting a stale deploy key
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, strum::Display)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum StaleKeyRule {
+    OldKey,
+    UnusedKey,
+}
+
+/// Options for the `deploy_keys` check
+#[derive(Debug, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct DeployKeysOptions {
+    /// Age in days after which deploy keys should be rotated
+    pub max_age_days: i64,
+    /// Number of days after which deploy keys are considered unused
+    pub max_unused_days: i64,
+    /// Rules whose keys are deleted with `--fix --delete-stale-deploy-keys`
+    pub delete: Vec<StaleKeyRule>,
+}
+
+impl Default for DeployKeysOptions {
+    fn default() -> Self {
+        Self {
+            max_age_days: 365,
+            max_unused_days: 90,
+            delete: vec![StaleKeyRule::UnusedKey],
+        }
+    }
+}
+
+/// Implementation for the `deploy_keys` check
+#[derive(Default, Debug, Clone, Copy)]
+pub struct DeployKeys;
+
+#[async_trait]
+impl RepositoryCheck for DeployKeys {
+    #[tracing::instrument(name = "deploy_keys", level = "info", skip_all)]
+    async fn run<'c>(
+        &self,
+        ctx: &'c CheckCtx<'c>,
+        repository: &Repository,
+    ) -> anyhow::Result<Vec<Finding>> {
+        let full_name = repository
+            .full_name
+            .as_ref()
+            .ok_or_else(|| anyhow!("missing full_name"))?;
+        let route = format!("/repos/{full_name}/keys");
+        let link = format!("https://github.com/{full_name}/settings/keys");
+        let options = &ctx.options.deploy_keys;
+
+        let keys: Vec<DeployKey> = get_all_pages(ctx.gh, format!("{route}?per_page=100")).await?;
+
+        let check = RepositoryChecks::DeployKeys(*self);
+        let delete_stale = ctx.args.delete_stale_deploy_keys;
+        let now = Utc::now();
+        let mut findings = vec![];
+        let mut fix_errors = vec![];
+
+        for key in &keys {
+            let subject = key.id.to_string();
+
+            if !key.read_only {
+                findings.push(
+                    Finding::new(
+                        "write_access",
+                        Severity::Warning,
+                        format!("deploy key {} has write access", key.title),
+                    )
+                    .with_subject(&subject)
+                    .with_link(&link),
+                );
+            }
+
+            let mut stale = vec![];
+
+            let age = (now - key.created_at).num_days();
+            if age > options.max_age_days {
+                stale.push((
+                    StaleKeyRule::OldKey,
+                    format!("deploy key {} was created {age} days ago", key.title),
+                ));
+            }
+
+            let recently_used = match key.last_used {
+                Some(last_used) => {
+                    let unused = (now - last_used).num_days();
+                    if unused > options.max_unused_days {
+                        stale.push((
+                            StaleKeyRule::UnusedKey,
+                            format!("deploy key {} was last used {unused} days ago", key.title),
+                        ));
+                    }
+
+                    unused <= options.max_unused_days
+                }
+                None => {
+                    if age > options.max_unused_days {
+                        stale.push((
+                            StaleKeyRule::UnusedKey,
+                            format!(
+                                "deploy key {} was created {age} days ago and never used",
+                                key.title
+                            ),
+                        ));
+                    }
+
+                    false
+                }
+            };
+
+            // Keys in use are never deleted, whatever the policy says
+            let deletable = delete_stale
+                && !recently_used
+                && stale.iter().any(|(rule, _)| options.delete.contains(rule));
+
+            // Only delete keys none of whose findings are suppressed
+            let delete = deletable
+                && stale
+                    .iter()
+                    .all(|(rule, _)| ctx.should_fix(check.clone(), &rule.to_string()));
+
+            for (rule, message) in stale {
+                let finding = Finding::new(rule.to_string(), Severity::Warning, message)
+                    .with_subject(&subject)
+                    .with_link(&link);
+
+                findings.push(if deletable && options.delete.contains(&rule) {
+                    finding.fixable()
+                } else {
+                    finding
+                });
+            }
+
+            if delete {
+                info!("deleting stale deploy key {}", key.title);
+                if let Err(err) = delete_no_content(ctx.gh, format!("{route}/{}", key.id)).await {
+                    fix_errors.push(err.into());
+                }
+            }
+        }
+
+        fixed(findings, fix_errors)
+    }
+}
@@ -11,7 +11,12 @@
 //!
 //! Again, GitHub added pushed this feature to production without a corresponding API (see the
 //! [discussion](https://github.com/orgs/community/discussions/35808)), so all this check can do is
-//! print a reminder with a link to the settings page for the corresponding repository.
+//! report a reminder with a link to the settings page for the corresponding repository. As the
+//! setting cannot be verified, the reminder is reported as `info`.
+//!
+//! Workflows triggered by `pull_request_target` or `workflow_run` are not subject to this approval.
+//! The ones running code from pull requests are reported by the `pwn_request` rule of the
+//! `workflow_files` check.
 //!
 //! # Sources
 //!
@@ -21,9 +26,9 @@
 use anyhow::anyhow;
 use async_trait::async_trait;
 use octocrab::models::Repository;
-use tracing::error;
 
 use super::{CheckCtx, RepositoryCheck};
+use crate::findings::{Finding, Severity};
 
 /// Implementation for the `fork_pull_request_workflows` check
 #[derive(Default, Debug, Clone, Copy)]
@@ -32,7 +37,11 @@ pub struct ForkPullRequestWorkflows;
 #[async_trait]
 impl RepositoryCheck for ForkPullRequestWorkflows {
     #[tracing::instrument(name = "fork_pull_request_workflows", level = "info", skip_all)]
-    async fn run<'c>(&self, _ctx: &'c CheckCtx<'c>, repository: &Repository) -> anyhow::Result<()> {
+    async fn run<'c>(
+        &self,
+        _ctx: &'c CheckCtx<'c>,
+        repository: &Repository,
+    ) -> anyhow::Result<Vec<Finding>> {
         let link = format!(
             "https://github.com/{}/settings/actions",
             repository
@@ -40,7 +49,11 @@ impl RepositoryCheck for ForkPullRequestWorkflows {
                 .as_ref()
                 .ok_or_else(|| anyhow!("missing repository full name"))?
         );
-        error!(link, "ghsec cannot programatically check or change settings for 'Fork pull request workflows from outside collaborators'. Go to {link} and make sure that the 'Require approval for first-time contributors' option is selected.");
-        Ok(())
+        Ok(vec![Finding::new(
+            "manual_check",
+            Severity::Info,
+            format!("ghsec cannot programatically check or change settings for 'Fork pull request workflows from outside collaborators'. Go to {link} and make sure that the 'Require approval for first-time contributors' option is selected."),
+        )
+        .with_link(link)])
     }
 }
@@ -0,0 +1,158 @@
+//! The `member_privileges` check ensures that the _Member privileges_ of an organization use
+//! secure values.
+//!
+//! The following settings are checked:
+//! - `default_repository_permission`: base permission granted to all members on all the
+//!   repositories of the organization. Should be `read` or `none`, since `write` or `admin` give
+//!   every member of the organization push access to every repository.
+//! - `members_can_create_public_repositories`: whether members can create public repositories.
+//!   Should be `false`, so publishing code is restricted to owners.
+//! - `members_can_fork_private_repositories`: whether members can fork private repositories to
+//!   their own account. Should be `false`, since forks escape the control of the organization.
+//! - `two_factor_requirement_enabled`: whether members are required to enable two-factor
+//!   authentication. Should be `true`.
+//!
+//! When running with `--fix`, this will set the first three settings to secure values:
+//! - `default_repository_permission`: `read`
+//! - `members_can_create_public_repositories`: `false`
+//! - `members_can_fork_private_repositories`: `false`
+//!
+//! Requiring two-factor authentication removes members that do not use it from the organization,
+//! and is not available through the API, so it has to be enabled manually.
+//!
+//! # Sources
+//!
+//! - [GitHub Docs](https://docs.github.com/en/organizations/managing-organization-settings)
+//! - [GitHub REST API](https://docs.github.com/en/rest/orgs/orgs?apiVersion=2022-11-28#update-an-organization)
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use tracing::info;
+
+use super::{fixed, CheckCtx, OrganizationCheck, OrganizationChecks};
+use crate::findings::{Finding, Severity};
+
+#[derive(Debug, Serialize, Deserialize)]
+struct OrganizationPrivileges {
+    // read, write, admin or none
+    #[serde(skip_serializing_if = "Option::is_none")]
+    default_repository_permission: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    members_can_create_public_repositories: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    members_can_fork_private_repositories: Option<bool>,
+    #[serde(skip_serializing)]
+    two_factor_requirement_enabled: Option<bool>,
+}
+
+/// Implementation for the `member_privileges` check
+#[derive(Default, Debug, Clone, Copy)]
+pub struct MemberPrivileges;
+
+#[async_trait]
+impl OrganizationCheck for MemberPrivileges {
+    #[tracing::instrument(name = "member_privileges", level = "info", skip_all)]
+    async fn run<'c>(
+        &self,
+        ctx: &'c CheckCtx<'c>,
+        organization: &str,
+    ) -> anyhow::Result<Vec<Finding>> {
+        let route = format!("/orgs/{organization}");
+        let link =
+            format!("https://github.com/organizations/{organization}/settings/member_privileges");
+
+        let privileges: OrganizationPrivileges =
+            ctx.gh.get(&route, Option::<()>::None.as_ref()).await?;
+
+        let mut findings = vec![];
+        let mut fix_errors = vec![];
+
+        // Only the settings that need fixing are sent when running with `--fix`
+        let mut fix = OrganizationPrivileges {
+            default_repository_permission: None,
+            members_can_create_public_repositories: None,
+            members_can_fork_private_repositories: None,
+            two_factor_requirement_enabled: None,
+        };
+
+        if let Some(permission @ ("write" | "admin")) =
+            privileges.default_repository_permission.as_deref()
+        {
+            findings.push(
+                Finding::new(
+                    "default_repository_permission",
+                    Severity::Warning,
+                    format!("default_repository_permission is set to {permission}"),
+                )
+                .with_link(&link)
+                .fixable(),
+            );
+            fix.default_repository_permission = Some("read".to_owned());
+        }
+
+        if privileges.members_can_create_public_repositories == Some(true) {
+            findings.push(
+                Finding::new(
+                    "members_can_create_public_repositories",
+                    Severity::Warning,
+                    "members_can_create_public_repositories is set to true",
+                )
+                .with_link(&link)
+                .fixable(),
+            );
+            fix.members_can_create_public_repositories = Some(false);
+        }
+
+        if privileges.members_can_fork_private_repositories == Some(true) {
+            findings.push(
+                Finding::new(
+                    "members_can_fork_private_repositories",
+                    Severity::Warning,
+                    "members_can_fork_private_repositories is set to true",
+                )
+                .with_link(&link)
+                .fixable(),
+            );
+            fix.members_can_fork_private_repositories = Some(false);
+        }
+
+        if privileges.two_factor_requirement_enabled == Some(false) {
+            findings.push(
+                Finding::new(
+                    "two_factor_requirement_enabled",
+                    Severity::Warning,
+                    "two-factor authentication is not required for members",
+                )
+                .with_link(format!(
+                    "https://github.com/organizations/{organization}/settings/security"
+                )),
+            );
+        }
+
+        if ctx.should_fix_any(OrganizationChecks::MemberPrivileges(*self), &findings) {
+            info!("fixing member privileges");
+
+            // Keep the current value of settings whose findings are suppressed
+            let check = OrganizationChecks::MemberPrivileges(*self);
+            if !ctx.should_fix(check.clone(), "default_repository_permission") {
+                fix.default_repository_permission = None;
+            }
+            if !ctx.should_fix(check.clone(), "members_can_create_public_repositories") {
+                fix.members_can_create_public_repositories = None;
+            }
+            if !ctx.should_fix(check, "members_can_fork_private_repositories") {
+                fix.members_can_fork_private_repositories = None;
+            }
+
+            if let Err(err) = ctx
+                .gh
+                .patch::<serde_json::Value, _, _>(route, Some(&fix))
+                .await
+            {
+                fix_errors.push(err.into());
+            }
+        }
+
+        fixed(findings, fix_errors)
+    }
+}
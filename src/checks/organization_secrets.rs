@@ -0,0 +1,117 @@
+//! The `organization_secrets` check lists GitHub Actions secrets that are defined in an
+//! organization.
+//!
+//! Organization secrets are shared with repositories of the organization, depending on their
+//! visibility: `all` repositories, `private` repositories, or `selected` repositories. Secrets
+//! available to all repositories, including public ones and repositories created in the future,
+//! increase the number of workflows that could leak them.
+//!
+//! If a secret name matches the `warn_secret_names` regular expression, or if the secret is
+//! available to all repositories, the secret will be reported as a warning instead of an
+//! information message.
+//!
+//! When running with `--fix`, this check currently does not do anything.
+//!
+//! # Options
+//!
+//! ```toml
+//! [organization_secrets]
+//! # Secrets that should trigger warnings instead of regular info
+//! warn_secret_names = "^GH_(TOKEN|PAT)$"
+//! ```
+//!
+//! # Sources
+//!
+//! - [GitHub Docs](https://docs.github.com/en/actions/security-guides/using-secrets-in-github-actions#creating-secrets-for-an-organization)
+//! - [GitHub REST API](https://docs.github.com/en/rest/actions/secrets?apiVersion=2022-11-28#list-organization-secrets)
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use tracing::info;
+
+use super::{get_all_secrets, CheckCtx, OrganizationCheck};
+use crate::findings::{Finding, Severity};
+
+#[derive(Debug, Serialize, Deserialize)]
+struct OrganizationSecret {
+    name: String,
+    created_at: DateTime<Utc>,
+    updated_at: DateTime<Utc>,
+    // all, private or selected
+    visibility: String,
+}
+
+/// Options for the `organization_secrets` check
+#[derive(Debug, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct OrganizationSecretsOptions {
+    /// Secrets that should trigger warnings instead of regular info
+    #[serde(deserialize_with = "crate::config::deserialize_regex")]
+    pub warn_secret_names: Regex,
+}
+
+impl Default for OrganizationSecretsOptions {
+    fn default() -> Self {
+        Self {
+            warn_secret_names: Regex::new("^GH_(TOKEN|PAT)$").unwrap(),
+        }
+    }
+}
+
+/// Implementation for the `organization_secrets` check
+#[derive(Default, Debug, Clone, Copy)]
+pub struct OrganizationSecrets;
+
+#[async_trait]
+impl OrganizationCheck for OrganizationSecrets {
+    #[tracing::instrument(name = "organization_secrets", level = "info", skip_all)]
+    async fn run<'c>(
+        &self,
+        ctx: &'c CheckCtx<'c>,
+        organization: &str,
+    ) -> anyhow::Result<Vec<Finding>> {
+        let secrets: Vec<OrganizationSecret> =
+            get_all_secrets(ctx.gh, format!("/orgs/{organization}/actions/secrets")).await?;
+
+        let link =
+            format!("https://github.com/organizations/{organization}/settings/secrets/actions");
+        let mut findings = vec![];
+
+        for secret in &secrets {
+            let finding = if ctx
+                .options
+                .organization_secrets
+                .warn_secret_names
+                .is_match(&secret.name)
+            {
+                Finding::new(
+                    "sensitive_secret",
+                    Severity::Warning,
+                    format!("found secret {}", secret.name),
+                )
+            } else if secret.visibility == "all" {
+                Finding::new(
+                    "secret_visible_to_all_repositories",
+                    Severity::Warning,
+                    format!("found secret {} available to all repositories", secret.name),
+                )
+            } else {
+                Finding::new(
+                    "secret",
+                    Severity::Info,
+                    format!("found secret {}", secret.name),
+                )
+            };
+
+            findings.push(finding.with_subject(&secret.name).with_link(&link));
+        }
+
+        if !secrets.is_empty() && ctx.args.fix {
+            info!("nothing to fix regarding secrets yet");
+        }
+
+        Ok(findings)
+    }
+}
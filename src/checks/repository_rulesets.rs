@@ -0,0 +1,271 @@
+//! The `repository_rulesets` check evaluates the rulesets that apply to the default branch of a
+//! repository.
+//!
+//! Rulesets are the successor of branch protections: they can be defined on a repository or on its
+//! organization, several of them can apply to the same branch, and their rules are layered to
+//! produce the effective rules of the branch. This check reports:
+//! - `bypass_actors`: an active ruleset covering the default branch can be bypassed by some
+//!   actors (roles, teams, apps, etc.), which defeats its purpose for these actors.
+//! - `evaluate_enforcement`: a ruleset covering the default branch is only evaluated, so its rules
+//!   are reported in rule insights but never enforced.
+//! - `missing_pull_request_rule`: the effective rules of the default branch do not require pull
+//!   requests before merging.
+//! - `missing_non_fast_forward_rule`: the effective rules of the default branch allow force
+//!   pushes.
+//!
+//! Missing rules are only reported when at least one active ruleset covers the default branch.
+//! Repositories relying on classic branch protections are covered by the `branch_protections`
+//! check instead.
+//!
+//! When running with `--fix`, this check currently does not do anything.
+//!
+//! # Sources
+//!
+//! - [GitHub Docs](https://docs.github.com/en/repositories/configuring-branches-and-merges-in-your-repository/managing-rulesets/about-rulesets)
+//! - [GitHub REST API](https://docs.github.com/en/rest/repos/rules?apiVersion=2022-11-28)
+
+use anyhow::anyhow;
+use async_trait::async_trait;
+use octocrab::models::Repository;
+use serde::{Deserialize, Serialize};
+use tracing::{info, warn};
+
+use super::{get_all_pages, CheckCtx, RepositoryCheck};
+use crate::findings::{Finding, Severity};
+
+/// A rule that applies to a branch, as returned by the `/rules/branches/{branch}` endpoint
+#[derive(Debug, Serialize, Deserialize)]
+pub(crate) struct BranchRule {
+    #[serde(rename = "type")]
+    pub rule_type: String,
+    pub ruleset_id: Option<i64>,
+    pub ruleset_source: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct Ruleset {
+    id: i64,
+    name: String,
+    // branch, tag or push
+    target: Option<String>,
+    source: String,
+    // disabled, active or evaluate
+    enforcement: String,
+    #[serde(default)]
+    bypass_actors: Vec<BypassActor>,
+    conditions: Option<RulesetConditions>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct BypassActor {
+    actor_id: Option<i64>,
+    // Integration, OrganizationAdmin, RepositoryRole, Team or DeployKey
+    actor_type: String,
+    // always or pull_request
+    bypass_mode: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct RulesetConditions {
+    ref_name: Option<RefNameCondition>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct RefNameCondition {
+    #[serde(default)]
+    include: Vec<String>,
+    #[serde(default)]
+    exclude: Vec<String>,
+}
+
+impl RefNameCondition {
+    /// `true` if one of the given ref name patterns matches the default branch
+    fn any_matches(patterns: &[String], default_branch: &str) -> bool {
+        patterns.iter().any(|pattern| match pattern.as_str() {
+            "~ALL" | "~DEFAULT_BRANCH" => true,
+            pattern => {
+                let pattern = pattern.strip_prefix("refs/heads/").unwrap_or(pattern);
+                glob::Pattern::new(pattern).is_ok_and(|pattern| pattern.matches(default_branch))
+            }
+        })
+    }
+
+    /// `true` if this condition targets the given default branch
+    fn matches(&self, default_branch: &str) -> bool {
+        Self::any_matches(&self.include, default_branch)
+            && !Self::any_matches(&self.exclude, default_branch)
+    }
+}
+
+impl Ruleset {
+    /// `true` if this ruleset targets the given default branch
+    fn covers(&self, default_branch: &str) -> bool {
+        self.target.as_deref().unwrap_or("branch") == "branch"
+            && self
+                .conditions
+                .as_ref()
+                .and_then(|conditions| conditions.ref_name.as_ref())
+                .is_some_and(|ref_name| ref_name.matches(default_branch))
+    }
+}
+
+/// Fetch the effective rules that apply to a branch of a repository
+pub(crate) async fn branch_rules(
+    ctx: &CheckCtx<'_>,
+    full_name: &str,
+    branch: &str,
+) -> octocrab::Result<Vec<BranchRule>> {
+    get_all_pages(
+        ctx.gh,
+        format!("/repos/{full_name}/rules/branches/{branch}?per_page=100"),
+    )
+    .await
+}
+
+/// Implementation for the `repository_rulesets` check
+#[derive(Default, Debug, Clone, Copy)]
+pub struct RepositoryRulesets;
+
+#[async_trait]
+impl RepositoryCheck for RepositoryRulesets {
+    #[tracing::instrument(name = "repository_rulesets", level = "info", skip_all)]
+    async fn run<'c>(
+        &self,
+        ctx: &'c CheckCtx<'c>,
+        repository: &Repository,
+    ) -> anyhow::Result<Vec<Finding>> {
+        let Some(default_branch) = &repository.default_branch else {
+            warn!("missing default branch information, cannot check rulesets for it");
+            return Ok(vec![]);
+        };
+
+        let full_name = repository
+            .full_name
+            .as_ref()
+            .ok_or_else(|| anyhow!("missing repository full name"))?;
+        let link = format!("https://github.com/{full_name}/settings/rules");
+
+        let rulesets = match get_all_pages::<Ruleset>(
+            ctx.gh,
+            format!("/repos/{full_name}/rulesets?includes_parents=true&per_page=100"),
+        )
+        .await
+        {
+            Ok(rulesets) => rulesets,
+            Err(octocrab::Error::GitHub { source, .. })
+                if source.message.contains("Upgrade to GitHub Pro") =>
+            {
+                return Ok(vec![Finding::new(
+                    "unavailable",
+                    Severity::Warning,
+                    "feature not available for this repository type",
+                )
+                .with_link(link)]);
+            }
+            Err(err) => return Err(err.into()),
+        };
+
+        let mut findings = vec![];
+        let mut covered = false;
+
+        for ruleset in rulesets
+            .iter()
+            .filter(|ruleset| ruleset.enforcement != "disabled")
+        {
+            // The list endpoint does not include conditions and bypass actors
+            let ruleset: Ruleset = ctx
+                .gh
+                .get(
+                    format!(
+                        "/repos/{full_name}/rulesets/{}?includes_parents=true",
+                        ruleset.id
+                    ),
+                    Option::<()>::None.as_ref(),
+                )
+                .await?;
+
+            if !ruleset.covers(default_branch) {
+                continue;
+            }
+
+            if ruleset.enforcement == "evaluate" {
+                findings.push(
+                    Finding::new(
+                        "evaluate_enforcement",
+                        Severity::Warning,
+                        format!(
+                            "ruleset {} from {} is only evaluated and not enforced on {default_branch}",
+                            ruleset.name, ruleset.source
+                        ),
+                    )
+                    .with_subject(&ruleset.name),
+                );
+
+                continue;
+            }
+
+            covered = true;
+
+            if !ruleset.bypass_actors.is_empty() {
+                let actors: Vec<_> = ruleset
+                    .bypass_actors
+                    .iter()
+                    .map(|actor| match actor.actor_id {
+                        Some(id) => format!("{} {id}", actor.actor_type),
+                        None => actor.actor_type.clone(),
+                    })
+                    .collect();
+
+                findings.push(
+                    Finding::new(
+                        "bypass_actors",
+                        Severity::Warning,
+                        format!(
+                            "ruleset {} from {} can be bypassed by {}",
+                            ruleset.name,
+                            ruleset.source,
+                            actors.join(", ")
+                        ),
+                    )
+                    .with_subject(&ruleset.name),
+                );
+            }
+        }
+
+        if covered {
+            let rules = branch_rules(ctx, full_name, default_branch).await?;
+            let has_rule = |rule_type: &str| rules.iter().any(|rule| rule.rule_type == rule_type);
+
+            if !has_rule("pull_request") {
+                findings.push(
+                    Finding::new(
+                        "missing_pull_request_rule",
+                        Severity::Warning,
+                        format!("rulesets do not require pull requests on {default_branch}"),
+                    )
+                    .with_subject(default_branch),
+                );
+            }
+
+            if !has_rule("non_fast_forward") {
+                findings.push(
+                    Finding::new(
+                        "missing_non_fast_forward_rule",
+                        Severity::Warning,
+                        format!("rulesets allow force pushes on {default_branch}"),
+                    )
+                    .with_subject(default_branch),
+                );
+            }
+        }
+
+        if !findings.is_empty() && ctx.args.fix {
+            info!("nothing to fix regarding rulesets yet");
+        }
+
+        Ok(findings
+            .into_iter()
+            .map(|finding| finding.with_link(&link))
+            .collect())
+    }
+}
@@ -1,15 +1,33 @@
 //! The `repository_secrets` lists secrets that are defined in a repository.
 //!
-//! Currently, this check only lists secret names found in the repository being analyzed. Since
-//! secrets often represent credentials (passwords, tokens, etc.) and are a high-value target, it
-//! makes sense to know if a repository contains secrets.
+//! This check lists secret names found in the repository being analyzed, along with the number of
+//! days since they were last updated. Since secrets often represent credentials (passwords,
+//! tokens, etc.) and are a high-value target, it makes sense to know if a repository contains
+//! secrets.
 //!
-//! If a secret name matches the `--repository-secrets-warn-secret-names` regular expression, the
-//! secret will be printed as a warning instead of an information message. This allows filtering
-//! more critical secrets from less critical ones.
+//! If a secret name matches the `warn_secret_names` regular expression, the secret will be
+//! reported as a warning instead of an information message. This allows filtering more critical
+//! secrets from less critical ones.
+//!
+//! Secrets that were not updated for more than `max_age_days` days are reported as
+//! `stale_secret`, so they can be rotated. Secrets matching `warn_secret_names` use the stricter
+//! `max_sensitive_age_days` limit instead.
 //!
 //! When running with `--fix`, this check currently does not do anything.
 //!
+//! # Options
+//!
+//! ```toml
+//! [repository_secrets]
+//! # Secrets that should trigger warnings instead of regular info. Can also be set using
+//! # `--repository-secrets-warn-secret-names`.
+//! warn_secret_names = "^GH_(TOKEN|PAT)$"
+//! # Age in days after which secrets should be rotated
+//! max_age_days = 365
+//! # Age in days after which secrets matching `warn_secret_names` should be rotated
+//! max_sensitive_age_days = 90
+//! ```
+//!
 //! # Sources
 //!
 //! - [GitHub Docs](https://docs.github.com/en/rest/actions/secrets?apiVersion=2022-11-28#list-repository-secrets)
@@ -19,16 +37,12 @@ use anyhow::anyhow;
 use async_trait::async_trait;
 use chrono::{DateTime, Utc};
 use octocrab::models::Repository;
+use regex::Regex;
 use serde::{Deserialize, Serialize};
-use tracing::{info, warn};
+use tracing::info;
 
-use super::{CheckCtx, RepositoryCheck};
-
-#[derive(Debug, Serialize, Deserialize)]
-struct SecretList {
-    total_count: i32,
-    secrets: Vec<Secret>,
-}
+use super::{get_all_secrets, CheckCtx, RepositoryCheck};
+use crate::findings::{Finding, Severity};
 
 #[derive(Debug, Serialize, Deserialize)]
 struct Secret {
@@ -37,6 +51,29 @@ struct Secret {
     updated_at: DateTime<Utc>,
 }
 
+/// Options for the `repository_secrets` check
+#[derive(Debug, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct RepositorySecretsOptions {
+    /// Secrets that should trigger warnings instead of regular info
+    #[serde(deserialize_with = "crate::config::deserialize_regex")]
+    pub warn_secret_names: Regex,
+    /// Age in days after which secrets should be rotated
+    pub max_age_days: i64,
+    /// Age in days after which secrets matching `warn_secret_names` should be rotated
+    pub max_sensitive_age_days: i64,
+}
+
+impl Default for RepositorySecretsOptions {
+    fn default() -> Self {
+        Self {
+            warn_secret_names: Regex::new("^GH_(TOKEN|PAT)$").unwrap(),
+            max_age_days: 365,
+            max_sensitive_age_days: 90,
+        }
+    }
+}
+
 /// Implementation for the `repository_secrets` check
 #[derive(Default, Debug, Clone, Copy)]
 pub struct RepositorySecrets;
@@ -44,39 +81,76 @@ pub struct RepositorySecrets;
 #[async_trait]
 impl RepositoryCheck for RepositorySecrets {
     #[tracing::instrument(name = "repository_secrets", level = "info", skip_all)]
-    async fn run<'c>(&self, ctx: &'c CheckCtx<'c>, repository: &Repository) -> anyhow::Result<()> {
-        let secrets: SecretList = ctx
-            .gh
-            .get(
-                format!(
-                    "/repos/{}/{}/actions/secrets",
-                    repository
-                        .owner
-                        .as_ref()
-                        .ok_or_else(|| anyhow!("missing owner"))?
-                        .login,
-                    repository.name
-                ),
-                Option::<()>::None.as_ref(),
-            )
-            .await?;
-
-        for secret in &secrets.secrets {
-            if ctx
-                .args
-                .repository_secrets_warn_secret_names
-                .is_match(&secret.name)
-            {
-                warn!(secret_name = secret.name, "found secret");
+    async fn run<'c>(
+        &self,
+        ctx: &'c CheckCtx<'c>,
+        repository: &Repository,
+    ) -> anyhow::Result<Vec<Finding>> {
+        let full_name = format!(
+            "{}/{}",
+            repository
+                .owner
+                .as_ref()
+                .ok_or_else(|| anyhow!("missing owner"))?
+                .login,
+            repository.name
+        );
+
+        let secrets: Vec<Secret> =
+            get_all_secrets(ctx.gh, format!("/repos/{full_name}/actions/secrets")).await?;
+
+        let link = format!("https://github.com/{full_name}/settings/secrets/actions");
+
+        let options = &ctx.options.repository_secrets;
+        let now = Utc::now();
+        let mut findings = vec![];
+
+        for secret in &secrets {
+            let sensitive = options.warn_secret_names.is_match(&secret.name);
+            let (rule, severity) = if sensitive {
+                ("sensitive_secret", Severity::Warning)
             } else {
-                info!(secret_name = secret.name, "found secret");
+                ("secret", Severity::Info)
+            };
+
+            let age = (now - secret.updated_at).num_days();
+
+            findings.push(
+                Finding::new(
+                    rule,
+                    severity,
+                    format!("found secret {}, last updated {age} days ago", secret.name),
+                )
+                .with_subject(&secret.name)
+                .with_link(&link),
+            );
+
+            let max_age = if sensitive {
+                options.max_sensitive_age_days
+            } else {
+                options.max_age_days
+            };
+
+            if age > max_age {
+                findings.push(
+                    Finding::new(
+                        "stale_secret",
+                        Severity::Warning,
+                        format!(
+                            "secret {} was last updated {age} days ago, it should be rotated every {max_age} days",
+                            secret.name
+                        ),
+                    )
+                    .with_subject(&secret.name)
+                    .with_link(&link),
+                );
             }
         }
 
-        if !secrets.secrets.is_empty() && ctx.args.fix {
+        if !secrets.is_empty() && ctx.args.fix {
             info!("nothing to fix regarding secrets yet");
         }
 
-        Ok(())
+        Ok(findings)
     }
 }
@@ -0,0 +1,189 @@
+//! The `secret_scanning` check ensures that secret scanning and its related features are enabled
+//! on repositories.
+//!
+//! Secret scanning detects credentials committed to a repository, such as API tokens or private
+//! keys, and alerts the repository owners. The following settings are checked:
+//! - `secret_scanning`: alerts for secrets found in the history of the repository.
+//! - `secret_scanning_push_protection`: blocks pushes containing secrets before they reach the
+//!   repository.
+//! - `secret_scanning_validity_checks`: checks whether detected secrets are still valid with their
+//!   provider, to prioritize alerts.
+//!
+//! Secret scanning is free for public repositories. On private repositories, it requires GitHub
+//! Advanced Security (or GitHub Secret Protection); settings that cannot be enabled with the
+//! current plan are reported as information only. Validity checks always require GitHub Advanced
+//! Security or GitHub Secret Protection, so they are reported as information on repositories
+//! without them.
+//!
+//! These settings are only visible to repository administrators. When they cannot be read, a
+//! single `unavailable` finding is reported.
+//!
+//! When running with `--fix`, this will enable secret scanning and push protection when they are
+//! disabled, where the plan of the repository allows it. Validity checks cannot be enabled through
+//! the REST API, and have to be enabled manually.
+//!
+//! # Sources
+//!
+//! - [GitHub Docs](https://docs.github.com/en/code-security/secret-scanning/introduction/about-secret-scanning)
+//! - [GitHub REST API](https://docs.github.com/en/rest/repos/repos?apiVersion=2022-11-28#update-a-repository)
+
+use anyhow::anyhow;
+use async_trait::async_trait;
+use octocrab::models::Repository;
+use serde::{Deserialize, Serialize};
+use tracing::info;
+
+use super::{fixed, CheckCtx, RepositoryCheck, RepositoryChecks};
+use crate::findings::{Finding, Severity};
+
+#[derive(Debug, Serialize, Deserialize)]
+struct RepositorySecurity {
+    security_and_analysis: Option<SecurityAndAnalysis>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct SecurityAndAnalysis {
+    #[serde(skip_serializing)]
+    advanced_security: Option<FeatureStatus>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    secret_scanning: Option<FeatureStatus>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    secret_scanning_push_protection: Option<FeatureStatus>,
+    // Not writable through the REST API
+    #[serde(skip_serializing)]
+    secret_scanning_validity_checks: Option<FeatureStatus>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct FeatureStatus {
+    // enabled or disabled
+    status: String,
+}
+
+impl FeatureStatus {
+    fn enabled() -> Self {
+        Self {
+            status: "enabled".to_owned(),
+        }
+    }
+
+    fn is_enabled(status: &Option<Self>) -> bool {
+        status
+            .as_ref()
+            .is_some_and(|status| status.status == "enabled")
+    }
+}
+
+/// Implementation for the `secret_scanning` check
+#[derive(Default, Debug, Clone, Copy)]
+pub struct SecretScanning;
+
+#[async_trait]
+impl RepositoryCheck for SecretScanning {
+    #[tracing::instrument(name = "secret_scanning", level = "info", skip_all)]
+    async fn run<'c>(
+        &self,
+        ctx: &'c CheckCtx<'c>,
+        repository: &Repository,
+    ) -> anyhow::Result<Vec<Finding>> {
+        let full_name = repository
+            .full_name
+            .as_ref()
+            .ok_or_else(|| anyhow!("missing full_name"))?;
+        let route = format!("/repos/{full_name}");
+        let link = format!("https://github.com/{full_name}/settings/security_analysis");
+
+        let security: RepositorySecurity = ctx.gh.get(&route, Option::<()>::None.as_ref()).await?;
+
+        let Some(settings) = security.security_and_analysis else {
+            return Ok(vec![Finding::new(
+                "unavailable",
+                Severity::Warning,
+                "security and analysis settings are not available, admin access is required",
+            )
+            .with_link(link)]);
+        };
+
+        // Secret scanning can only be enabled on private repositories with GitHub Advanced
+        // Security or GitHub Secret Protection
+        let secret_protection = FeatureStatus::is_enabled(&settings.advanced_security)
+            || repository.private == Some(true)
+                && FeatureStatus::is_enabled(&settings.secret_scanning);
+        let available = repository.private != Some(true) || secret_protection;
+
+        let mut findings = vec![];
+        let mut fix_errors = vec![];
+
+        // Only the settings that need fixing are sent when running with `--fix`
+        let mut fix = SecurityAndAnalysis::default();
+
+        let features = [
+            (
+                "secret_scanning",
+                &settings.secret_scanning,
+                &mut fix.secret_scanning,
+            ),
+            (
+                "secret_scanning_push_protection",
+                &settings.secret_scanning_push_protection,
+                &mut fix.secret_scanning_push_protection,
+            ),
+        ];
+
+        for (feature, status, fix) in features {
+            if FeatureStatus::is_enabled(status) {
+                continue;
+            }
+
+            let finding = if available {
+                if ctx.should_fix(RepositoryChecks::SecretScanning(*self), feature) {
+                    *fix = Some(FeatureStatus::enabled());
+                }
+
+                Finding::new(feature, Severity::Warning, format!("{feature} is disabled")).fixable()
+            } else {
+                Finding::new(
+                    feature,
+                    Severity::Info,
+                    format!("{feature} is disabled, and requires GitHub Advanced Security"),
+                )
+            };
+
+            findings.push(finding.with_link(&link));
+        }
+
+        if !FeatureStatus::is_enabled(&settings.secret_scanning_validity_checks) {
+            let feature = "secret_scanning_validity_checks";
+            let finding = if secret_protection {
+                Finding::new(feature, Severity::Warning, format!("{feature} is disabled"))
+            } else {
+                Finding::new(
+                    feature,
+                    Severity::Info,
+                    format!("{feature} is disabled, and requires GitHub Advanced Security"),
+                )
+            };
+
+            findings.push(finding.with_link(&link));
+        }
+
+        if ctx.should_fix_any(RepositoryChecks::SecretScanning(*self), &findings) {
+            info!("enabling secret scanning");
+
+            if let Err(err) = ctx
+                .gh
+                .patch::<serde_json::Value, _, _>(
+                    route,
+                    Some(&RepositorySecurity {
+                        security_and_analysis: Some(fix),
+                    }),
+                )
+                .await
+            {
+                fix_errors.push(err.into());
+            }
+        }
+
+        fixed(findings, fix_errors)
+    }
+}
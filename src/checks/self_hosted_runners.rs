@@ -0,0 +1,184 @@
+//! The `self_hosted_runners` check reports public repositories that can run workflows on
+//! self-hosted runners.
+//!
+//! Self-hosted runners execute workflow jobs on infrastructure managed by the owner of the
+//! repository. On public repositories, anyone can open a pull request from a fork and change the
+//! workflows it runs, so a job running on a self-hosted runner lets strangers execute code on that
+//! infrastructure, and possibly persist on it between jobs. GitHub recommends to never use
+//! self-hosted runners with public repositories.
+//!
+//! This check only applies to public repositories, and reports:
+//! - `registered_runner`: a self-hosted runner is registered on the repository.
+//! - `self_hosted_job`: a workflow job runs on `self-hosted`, or on a label of a runner registered
+//!   on the repository. The finding is reported as an error if the workflow can be triggered by
+//!   pull requests.
+//!
+//! Runners registered on an organization and shared with the repository through runner groups are
+//! not listed by this check, but jobs targeting them with the `self-hosted` label are reported.
+//!
+//! When running with `--fix`, this check currently does not do anything.
+//!
+//! # Sources
+//!
+//! - [GitHub Docs](https://docs.github.com/en/actions/hosting-your-own-runners/managing-self-hosted-runners/about-self-hosted-runners#self-hosted-runner-security)
+//! - [GitHub REST API](https://docs.github.com/en/rest/actions/self-hosted-runners?apiVersion=2022-11-28#list-self-hosted-runners-for-a-repository)
+
+use std::collections::HashSet;
+
+use anyhow::anyhow;
+use async_trait::async_trait;
+use octocrab::models::Repository;
+use serde::{Deserialize, Serialize};
+use tracing::{info, warn};
+
+use super::{fetch_workflows, get_all_pages, is_public, CheckCtx, Node, RepositoryCheck};
+use crate::findings::{Finding, Severity};
+
+/// Triggers running workflows for pull requests, including pull requests from forks
+const PULL_REQUEST_TRIGGERS: &[&str] = &["pull_request", "pull_request_target"];
+
+#[derive(Debug, Serialize, Deserialize)]
+struct Runner {
+    id: i64,
+    name: String,
+    os: String,
+    // online or offline
+    status: String,
+    labels: Vec<RunnerLabel>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct RunnerLabel {
+    name: String,
+    // read-only or custom
+    #[serde(rename = "type")]
+    label_type: Option<String>,
+}
+
+/// Labels of the runners a job can run on
+fn runs_on(job: &Node) -> Vec<&str> {
+    let Some(runs_on) = job.get("runs-on") else {
+        return vec![];
+    };
+
+    // runs-on can be a label, a list of labels, or a mapping with a group and labels
+    let labels = runs_on.get("labels").unwrap_or(runs_on);
+
+    labels
+        .as_str()
+        .into_iter()
+        .chain(labels.items().iter().filter_map(Node::as_str))
+        .collect()
+}
+
+/// Implementation for the `self_hosted_runners` check
+#[derive(Default, Debug, Clone, Copy)]
+pub struct SelfHostedRunners;
+
+#[async_trait]
+impl RepositoryCheck for SelfHostedRunners {
+    #[tracing::instrument(name = "self_hosted_runners", level = "info", skip_all)]
+    async fn run<'c>(
+        &self,
+        ctx: &'c CheckCtx<'c>,
+        repository: &Repository,
+    ) -> anyhow::Result<Vec<Finding>> {
+        if !is_public(repository) {
+            return Ok(vec![]);
+        }
+
+        let full_name = repository
+            .full_name
+            .as_ref()
+            .ok_or_else(|| anyhow!("missing repository full name"))?;
+
+        let runners: Vec<Runner> = get_all_pages(
+            ctx.gh,
+            format!("/repos/{full_name}/actions/runners?per_page=100"),
+        )
+        .await?;
+
+        let link = format!("https://github.com/{full_name}/settings/actions/runners");
+        let mut findings = vec![];
+
+        for runner in &runners {
+            findings.push(
+                Finding::new(
+                    "registered_runner",
+                    Severity::Warning,
+                    format!(
+                        "self-hosted runner {} ({}, {}) is registered on a public repository",
+                        runner.name, runner.os, runner.status
+                    ),
+                )
+                .with_subject(&runner.name)
+                .with_link(&link),
+            );
+        }
+
+        // Custom labels identify registered runners, read-only ones (OS, architecture) do not
+        let runner_labels: HashSet<&str> = runners
+            .iter()
+            .flat_map(|runner| &runner.labels)
+            .filter(|label| label.label_type.as_deref() == Some("custom"))
+            .map(|label| label.name.as_str())
+            .collect();
+
+        if let Some(default_branch) = &repository.default_branch {
+            for file in fetch_workflows(ctx, full_name, default_branch).await? {
+                // Invalid workflows are reported by the `workflow_files` check
+                let Ok(workflow) = file.parse() else {
+                    continue;
+                };
+
+                let pull_requests: Vec<_> = workflow
+                    .triggers()
+                    .filter(|trigger| PULL_REQUEST_TRIGGERS.contains(trigger))
+                    .collect();
+
+                for (job_id, job) in workflow.jobs() {
+                    let labels = runs_on(job);
+                    let Some(label) = labels.iter().find(|label| {
+                        label.eq_ignore_ascii_case("self-hosted") || runner_labels.contains(*label)
+                    }) else {
+                        continue;
+                    };
+
+                    let (severity, message) = if pull_requests.is_empty() {
+                        (
+                            Severity::Warning,
+                            format!("job {job_id} runs on self-hosted runner label {label}"),
+                        )
+                    } else {
+                        (
+                            Severity::Error,
+                            format!(
+                                "job {job_id} runs on self-hosted runner label {label} and can be triggered by {} from forks",
+                                pull_requests.join(", ")
+                            ),
+                        )
+                    };
+
+                    findings.push(
+                        workflow
+                            .finding(
+                                "self_hosted_job",
+                                severity,
+                                message,
+                                job.get("runs-on").unwrap_or(job),
+                            )
+                            .with_subject(workflow.subject(&[job_id])),
+                    );
+                }
+            }
+        } else {
+            warn!("missing default branch information, cannot check workflow files");
+        }
+
+        if !findings.is_empty() && ctx.args.fix {
+            info!("nothing to fix regarding self-hosted runners yet");
+        }
+
+        Ok(findings)
+    }
+}
@@ -0,0 +1,139 @@
+//! The `vulnerability_reporting` check ensures that public repositories tell security researchers
+//! how to report vulnerabilities privately.
+//!
+//! Without a private channel, vulnerabilities tend to be reported in public issues, disclosing
+//! them before a fix is available. This check only applies to public repositories, and reports:
+//! - `private_vulnerability_reporting`: private vulnerability reporting is disabled, so
+//!   researchers cannot submit a draft security advisory to the repository.
+//! - `missing_security_policy`: no `SECURITY.md` file was found in the root, `.github` or `docs`
+//!   directories of the repository, nor in the `.github` repository of its owner, which provides
+//!   the default security policy of an account or organization.
+//!
+//! When running with `--fix`, this will enable private vulnerability reporting. Security policies
+//! have to be written manually.
+//!
+//! # Sources
+//!
+//! - [GitHub Docs](https://docs.github.com/en/code-security/security-advisories/working-with-repository-security-advisories/configuring-private-vulnerability-reporting-for-a-repository)
+//! - [GitHub Docs (security policy)](https://docs.github.com/en/code-security/getting-started/adding-a-security-policy-to-your-repository)
+//! - [GitHub REST API](https://docs.github.com/en/rest/repos/repos?apiVersion=2022-11-28#check-if-private-vulnerability-reporting-is-enabled-for-a-repository)
+
+use anyhow::anyhow;
+use async_trait::async_trait;
+use octocrab::models::Repository;
+use serde::{Deserialize, Serialize};
+use tracing::{debug, info};
+
+use super::{
+    fixed, is_missing_content, is_public, put_no_content, CheckCtx, RepositoryCheck,
+    RepositoryChecks,
+};
+use crate::findings::{Finding, Severity};
+
+/// Locations GitHub looks for a security policy in, by order of precedence
+const SECURITY_POLICY_PATHS: &[&str] = &[".github/SECURITY.md", "SECURITY.md", "docs/SECURITY.md"];
+
+#[derive(Debug, Serialize, Deserialize)]
+struct PrivateVulnerabilityReporting {
+    enabled: bool,
+}
+
+/// Path to the security policy of the given repository, if any
+async fn security_policy(
+    ctx: &CheckCtx<'_>,
+    owner: &str,
+    name: &str,
+) -> anyhow::Result<Option<String>> {
+    for path in SECURITY_POLICY_PATHS {
+        match ctx
+            .gh
+            .repos(owner, name)
+            .get_content()
+            .path(*path)
+            .send()
+            .await
+        {
+            Ok(_) => return Ok(Some(format!("{owner}/{name}/{path}"))),
+            Err(err) if is_missing_content(&err) => {}
+            Err(err) => return Err(err.into()),
+        }
+    }
+
+    Ok(None)
+}
+
+/// Implementation for the `vulnerability_reporting` check
+#[derive(Default, Debug, Clone, Copy)]
+pub struct VulnerabilityReporting;
+
+#[async_trait]
+impl RepositoryCheck for VulnerabilityReporting {
+    #[tracing::instrument(name = "vulnerability_reporting", level = "info", skip_all)]
+    async fn run<'c>(
+        &self,
+        ctx: &'c CheckCtx<'c>,
+        repository: &Repository,
+    ) -> anyhow::Result<Vec<Finding>> {
+        if !is_public(repository) {
+            return Ok(vec![]);
+        }
+
+        let owner = &repository
+            .owner
+            .as_ref()
+            .ok_or_else(|| anyhow!("missing owner"))?
+            .login;
+        let full_name = format!("{owner}/{}", repository.name);
+        let route = format!("/repos/{full_name}/private-vulnerability-reporting");
+
+        let mut findings = vec![];
+        let mut fix_errors = vec![];
+
+        let reporting: PrivateVulnerabilityReporting =
+            ctx.gh.get(&route, Option::<()>::None.as_ref()).await?;
+
+        if !reporting.enabled {
+            findings.push(
+                Finding::new(
+                    "private_vulnerability_reporting",
+                    Severity::Warning,
+                    "private vulnerability reporting is disabled",
+                )
+                .with_link(format!(
+                    "https://github.com/{full_name}/settings/security_analysis"
+                ))
+                .fixable(),
+            );
+
+            if ctx.should_fix(
+                RepositoryChecks::VulnerabilityReporting(*self),
+                "private_vulnerability_reporting",
+            ) {
+                info!("enabling private vulnerability reporting");
+                if let Err(err) = put_no_content(ctx.gh, &route, Option::<&()>::None).await {
+                    fix_errors.push(err.into());
+                }
+            }
+        }
+
+        // The .github repository of the owner provides default community health files
+        let policy = match security_policy(ctx, owner, &repository.name).await? {
+            Some(policy) => Some(policy),
+            None => security_policy(ctx, owner, ".github").await?,
+        };
+
+        match policy {
+            Some(policy) => debug!("found security policy at {policy}"),
+            None => findings.push(
+                Finding::new(
+                    "missing_security_policy",
+                    Severity::Warning,
+                    format!("no SECURITY.md found in {full_name} or {owner}/.github"),
+                )
+                .with_link(format!("https://github.com/{full_name}/security/policy")),
+            ),
+        }
+
+        fixed(findings, fix_errors)
+    }
+}
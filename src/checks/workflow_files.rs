@@ -0,0 +1,333 @@
+//! The `workflow_files` check analyzes the GitHub Actions workflow files of a repository.
+//!
+//! Most compromises of GitHub Actions do not come from repository settings, but from the
+//! workflows themselves: a workflow running untrusted code with access to secrets, or
+//! interpolating attacker-controlled values in scripts, can leak credentials or allow pushing to
+//! the repository. This check fetches the workflow files (`.github/workflows/*.yml`) from the
+//! default branch of the repository, and reports dangerous patterns with the file and line they
+//! were found at.
+//!
+//! The following rules are currently implemented:
+//! - `expression_injection`: a `run` script or an `actions/github-script` script interpolates an
+//!   attacker-controlled context, such as `github.event.issue.title` or `github.head_ref`, using
+//!   `${{ }}`. The value is pasted in the script before it runs, so a crafted issue title or
+//!   branch name can execute arbitrary commands. Such values should be passed through an `env`
+//!   variable instead.
+//! - `invalid_workflow`: the workflow file is not valid YAML, and cannot be analyzed.
+//! - `missing_permissions`: a job has no `permissions` block, and neither does its workflow. The
+//!   job then gets the default permissions of the repository, which the
+//!   `default_workflow_permissions` check expects to be `read`, but which may change later or be
+//!   inherited from an organization. Workflows should declare the permissions they need.
+//! - `pwn_request`: the workflow is triggered by `pull_request_target` or `workflow_run`, checks
+//!   out the head of the pull request (`github.event.pull_request.head.sha`, `github.head_ref`,
+//!   etc.) and then runs build steps. These triggers run with access to secrets and a read-write
+//!   `GITHUB_TOKEN` even for pull requests from forks, so the code of any pull request can steal
+//!   them. This is the classic "pwn request" vulnerability.
+//! - `unpinned_action`: a step uses a third-party action, or a job calls a third-party reusable
+//!   workflow, by tag or branch instead of a full commit SHA. Tags and branches can be moved by
+//!   the owner of the action, or by anyone compromising it, to run different code in the
+//!   workflow. Actions from owners listed in `trusted_action_owners` are not reported.
+//! - `unsecure_commands`: the workflow sets `ACTIONS_ALLOW_UNSECURE_COMMANDS`, which re-enables
+//!   the deprecated `set-env` and `add-path` workflow commands. Any step printing untrusted data
+//!   can then inject environment variables such as `NODE_OPTIONS` into later steps.
+//! - `workflow_write_permissions`: the workflow grants a write scope in its top-level
+//!   `permissions`, which applies to all its jobs. Write scopes should only be granted to the jobs
+//!   that need them.
+//! - `write_all_permissions`: the workflow or a job requests `write-all` permissions.
+//!
+//! When running with `--fix`, unpinned actions are resolved to the commit SHA their tag or branch
+//! currently points to. The rewritten workflows are pushed to a `ghsec/pin-actions` branch, with
+//! the original reference kept as a trailing comment (`uses: owner/action@<sha> # v4`), and a pull
+//! request is opened against the default branch. A leftover branch from a previous run is reset to
+//! the default branch, and the pull request already open for it is updated instead.
+//! Other rules have to be fixed manually.
+//!
+//! # Options
+//!
+//! ```toml
+//! [workflow_files]
+//! # Owners of actions that do not need to be pinned to a commit SHA
+//! trusted_action_owners = ["actions", "github"]
+//! ```
+//!
+//! # Sources
+//!
+//! - [GitHub Docs](https://docs.github.com/en/actions/security-guides/security-hardening-for-github-actions)
+//! - [GitHub REST API](https://docs.github.com/en/rest/repos/contents?apiVersion=2022-11-28#get-repository-content)
+
+use anyhow::{anyhow, Context};
+use async_trait::async_trait;
+use octocrab::models::Repository;
+use serde::Deserialize;
+use tracing::warn;
+
+use super::{fixed, is_missing_content, CheckCtx, RepositoryCheck, RepositoryChecks};
+use crate::findings::{Finding, Severity};
+
+mod expression_injection;
+mod permissions;
+mod pwn_request;
+mod unpinned_actions;
+mod unsecure_commands;
+
+mod yaml;
+pub(crate) use yaml::Node;
+
+/// Directory containing the workflow files of a repository
+const WORKFLOWS_PATH: &str = ".github/workflows";
+
+/// Options for the `workflow_files` check
+#[derive(Debug, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct WorkflowFilesOptions {
+    /// Owners of actions that do not need to be pinned to a commit SHA
+    pub trusted_action_owners: Vec<String>,
+}
+
+impl Default for WorkflowFilesOptions {
+    fn default() -> Self {
+        Self {
+            trusted_action_owners: vec!["actions".to_owned(), "github".to_owned()],
+        }
+    }
+}
+
+/// A workflow file fetched from a repository
+#[derive(Debug)]
+pub(crate) struct WorkflowFile {
+    /// Path to the file, relative to the root of the repository
+    pub path: String,
+    /// Blob SHA of the file, required for updating it
+    pub sha: String,
+    /// Contents of the file
+    pub content: String,
+    /// URL to the file on GitHub
+    pub html_url: String,
+}
+
+impl WorkflowFile {
+    /// Parse the contents of this workflow file
+    pub fn parse(&self) -> Result<Workflow<'_>, yaml_rust2::ScanError> {
+        Ok(Workflow {
+            file: self,
+            root: yaml::parse(&self.content)?,
+        })
+    }
+}
+
+#[cfg(test)]
+impl WorkflowFile {
+    /// Workflow file with the given contents, for testing detectors
+    pub fn test(content: &str) -> Self {
+        Self {
+            path: ".github/workflows/test.yml".to_owned(),
+            sha: String::new(),
+            content: content.to_owned(),
+            html_url: String::new(),
+        }
+    }
+}
+
+/// A parsed workflow file
+pub(crate) struct Workflow<'f> {
+    pub file: &'f WorkflowFile,
+    pub root: Node,
+}
+
+impl Workflow<'_> {
+    /// Events triggering this workflow
+    pub fn triggers(&self) -> impl Iterator<Item = &str> {
+        let on = self.root.get("on");
+
+        on.and_then(Node::as_str)
+            .into_iter()
+            .chain(
+                on.into_iter()
+                    .flat_map(|on| on.items())
+                    .filter_map(Node::as_str),
+            )
+            .chain(
+                on.into_iter()
+                    .flat_map(|on| on.entries())
+                    .map(|(key, _)| key),
+            )
+    }
+
+    /// Jobs of this workflow, by identifier
+    pub fn jobs(&self) -> impl Iterator<Item = (&str, &Node)> {
+        self.root
+            .get("jobs")
+            .into_iter()
+            .flat_map(|jobs| jobs.entries())
+    }
+
+    /// Steps of the given job, with a label identifying them within the job
+    pub fn steps(job: &Node) -> impl Iterator<Item = (String, &Node)> {
+        job.get("steps")
+            .into_iter()
+            .flat_map(|steps| steps.items())
+            .enumerate()
+            .map(|(index, step)| {
+                let label = ["id", "name", "uses"]
+                    .into_iter()
+                    .find_map(|key| step.get(key)?.as_str())
+                    .map(ToOwned::to_owned)
+                    .unwrap_or_else(|| format!("step {}", index + 1));
+
+                (label, step)
+            })
+    }
+
+    /// Subject identifying a part of this workflow, for findings
+    pub fn subject(&self, parts: &[&str]) -> String {
+        std::iter::once(self.file.path.as_str())
+            .chain(parts.iter().copied())
+            .collect::<Vec<_>>()
+            .join(":")
+    }
+
+    /// Create a finding located at the given node of this workflow
+    pub fn finding(
+        &self,
+        rule: &str,
+        severity: Severity,
+        message: impl Into<String>,
+        node: &Node,
+    ) -> Finding {
+        self.finding_at(rule, severity, message, node.line)
+    }
+
+    /// Create a finding located at the given line of this workflow
+    pub fn finding_at(
+        &self,
+        rule: &str,
+        severity: Severity,
+        message: impl Into<String>,
+        line: usize,
+    ) -> Finding {
+        Finding::new(rule, severity, message)
+            .with_subject(&self.file.path)
+            .with_location(&self.file.path, Some(line))
+            .with_link(format!("{}#L{line}", self.file.html_url))
+    }
+}
+
+/// Fetch the workflow files of a repository at the given branch
+pub(crate) async fn fetch_workflows(
+    ctx: &CheckCtx<'_>,
+    full_name: &str,
+    branch: &str,
+) -> anyhow::Result<Vec<WorkflowFile>> {
+    let (owner, name) = full_name
+        .split_once('/')
+        .ok_or_else(|| anyhow!("invalid repository name {full_name}"))?;
+    let repos = ctx.gh.repos(owner, name);
+
+    let entries = match repos
+        .get_content()
+        .path(WORKFLOWS_PATH)
+        .r#ref(branch)
+        .send()
+        .await
+    {
+        Ok(entries) => entries.items,
+        Err(err) if is_missing_content(&err) => return Ok(vec![]),
+        Err(err) => return Err(err.into()),
+    };
+
+    let mut files = vec![];
+
+    for entry in entries {
+        if entry.r#type != "file"
+            || !(entry.name.ends_with(".yml") || entry.name.ends_with(".yaml"))
+        {
+            continue;
+        }
+
+        let file = repos
+            .get_content()
+            .path(&entry.path)
+            .r#ref(branch)
+            .send()
+            .await?
+            .items
+            .into_iter()
+            .next()
+            .ok_or_else(|| anyhow!("missing contents for {}", entry.path))?;
+
+        files.push(WorkflowFile {
+            content: file.decoded_content().unwrap_or_default(),
+            html_url: format!("https://github.com/{full_name}/blob/{branch}/{}", file.path),
+            path: file.path,
+            sha: file.sha,
+        });
+    }
+
+    Ok(files)
+}
+
+/// Implementation for the `workflow_files` check
+#[derive(Default, Debug, Clone, Copy)]
+pub struct WorkflowFiles;
+
+#[async_trait]
+impl RepositoryCheck for WorkflowFiles {
+    #[tracing::instrument(name = "workflow_files", level = "info", skip_all)]
+    async fn run<'c>(
+        &self,
+        ctx: &'c CheckCtx<'c>,
+        repository: &Repository,
+    ) -> anyhow::Result<Vec<Finding>> {
+        let Some(default_branch) = &repository.default_branch else {
+            warn!("missing default branch information, cannot check workflow files");
+            return Ok(vec![]);
+        };
+
+        let full_name = repository
+            .full_name
+            .as_ref()
+            .ok_or_else(|| anyhow!("missing repository full name"))?;
+
+        let files = fetch_workflows(ctx, full_name, default_branch).await?;
+        let options = &ctx.options.workflow_files;
+        let mut findings = vec![];
+
+        for file in &files {
+            match file.parse() {
+                Ok(workflow) => {
+                    findings.extend(expression_injection::check(&workflow));
+                    findings.extend(permissions::check(&workflow));
+                    findings.extend(pwn_request::check(&workflow));
+                    findings.extend(unpinned_actions::check(&workflow, options));
+                    findings.extend(unsecure_commands::check(&workflow));
+                }
+                Err(err) => {
+                    let line = err.marker().line();
+
+                    findings.push(
+                        Finding::new(
+                            "invalid_workflow",
+                            Severity::Warning,
+                            format!("failed to parse workflow {}: {}", file.path, err.info()),
+                        )
+                        .with_subject(&file.path)
+                        .with_location(&file.path, Some(line))
+                        .with_link(format!("{}#L{line}", file.html_url)),
+                    );
+                }
+            }
+        }
+
+        let mut fix_errors = vec![];
+        if ctx.should_fix_any(RepositoryChecks::WorkflowFiles(*self), &findings) {
+            fix_errors.extend(
+                unpinned_actions::fix(ctx, full_name, default_branch, &files, options)
+                    .await
+                    .context("failed to pin actions")
+                    .err(),
+            );
+        }
+
+        fixed(findings, fix_errors)
+    }
+}
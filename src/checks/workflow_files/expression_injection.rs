@@ -0,0 +1,184 @@
+//! Detection of attacker-controlled values interpolated in scripts
+
+use std::sync::OnceLock;
+
+use regex::Regex;
+
+use super::{Node, Workflow};
+use crate::findings::{Finding, Severity};
+
+/// Contexts that can be controlled by anyone able to open an issue, a pull request, a comment, etc.
+///
+/// See the list of untrusted inputs in the
+/// [GitHub Docs](https://docs.github.com/en/actions/security-guides/security-hardening-for-github-actions#understanding-the-risk-of-script-injections).
+fn untrusted_contexts() -> &'static Regex {
+    static REGEX: OnceLock<Regex> = OnceLock::new();
+
+    REGEX.get_or_init(|| {
+        Regex::new(concat!(
+            r"github\.head_ref\b",
+            r"|github\.event\.(issue|pull_request|discussion)\.(title|body)\b",
+            r"|github\.event\.(comment|review|review_comment)\.body\b",
+            r"|github\.event\.pages(\[\d+\]|\.\*)\.page_name\b",
+            r"|github\.event\.(commits(\[\d+\]|\.\*)|head_commit|workflow_run\.head_commit)\.(message|author\.(email|name))\b",
+            r"|github\.event\.pull_request\.head\.(ref|label|repo\.default_branch)\b",
+            r"|github\.event\.workflow_run\.(head_branch|display_title)\b",
+        ))
+        .unwrap()
+    })
+}
+
+/// Expressions interpolating untrusted contexts in the given script, with their line
+fn injections(script: &Node) -> Vec<(usize, String)> {
+    let Some(source) = script.as_str() else {
+        return vec![];
+    };
+
+    let mut injections = vec![];
+    let mut rest = source;
+
+    while let Some(start) = rest.find("${{") {
+        let Some(end) = rest[start..].find("}}") else {
+            break;
+        };
+
+        let expression = rest[start + 3..start + end].trim();
+        if untrusted_contexts().is_match(expression) {
+            // Block scalars start on the line after their indicator, which is where the node is
+            let offset = source.len() - rest.len() + start;
+            let line = script.line + source[..offset].matches('\n').count();
+
+            injections.push((line, expression.to_owned()));
+        }
+
+        rest = &rest[start + end + 2..];
+    }
+
+    injections
+}
+
+/// Language of a step script
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ScriptKind {
+    /// Shell script of a `run` step
+    Shell,
+    /// JavaScript of an `actions/github-script` step
+    GitHubScript,
+}
+
+impl ScriptKind {
+    /// How a script of this kind reads the `VALUE` environment variable
+    fn env_usage(self) -> &'static str {
+        match self {
+            Self::Shell => "\"$VALUE\"",
+            Self::GitHubScript => "process.env.VALUE",
+        }
+    }
+}
+
+/// Script of the given step, if it runs one
+fn script(step: &Node) -> Option<(&Node, ScriptKind)> {
+    if let Some(run) = step.get("run") {
+        return Some((run, ScriptKind::Shell));
+    }
+
+    step.get("uses")?
+        .as_str()
+        .filter(|uses| uses.starts_with("actions/github-script@"))?;
+    let script = step.get("with")?.get("script")?;
+    Some((script, ScriptKind::GitHubScript))
+}
+
+/// Report steps interpolating untrusted contexts in `run` scripts and `actions/github-script`
+pub(super) fn check(workflow: &Workflow) -> Vec<Finding> {
+    let mut findings = vec![];
+
+    for (job_id, job) in workflow.jobs() {
+        for (step_label, step) in Workflow::steps(job) {
+            let Some((script, kind)) = script(step) else {
+                continue;
+            };
+
+            for (line, expression) in injections(script) {
+                findings.push(
+                    workflow
+                        .finding_at(
+                            "expression_injection",
+                            Severity::Error,
+                            format!(
+                                "step {step_label} of job {job_id} interpolates ${{{{ {expression} }}}} in its script: pass it through an env variable instead (env: VALUE: ${{{{ {expression} }}}}) and use {}",
+                                kind.env_usage(),
+                            ),
+                            line,
+                        )
+                        .with_subject(workflow.subject(&[job_id, &step_label, &expression])),
+                );
+            }
+        }
+    }
+
+    findings
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::WorkflowFile;
+    use super::*;
+
+    fn findings(content: &str) -> Vec<Finding> {
+        check(&WorkflowFile::test(content).parse().unwrap())
+    }
+
+    #[test]
+    fn run_block() {
+        let findings = findings(
+            r#"on: issues
+jobs:
+  triage:
+    runs-on: ubuntu-latest
+    steps:
+      - run: |
+          echo "new issue"
+          echo "${{ github.event.issue.title }}"
+"#,
+        );
+
+        assert_eq!(findings.len(), 1);
+        assert_eq!(findings[0].rule, "expression_injection");
+        assert_eq!(findings[0].location.as_ref().unwrap().line, Some(8));
+        assert!(findings[0].message.contains("\"$VALUE\""));
+    }
+
+    #[test]
+    fn github_script() {
+        let findings = findings(
+            r#"on: issue_comment
+jobs:
+  triage:
+    runs-on: ubuntu-latest
+    steps:
+      - uses: actions/github-script@v7
+        with:
+          script: console.log("${{ github.event.comment.body }}")
+"#,
+        );
+
+        assert_eq!(findings.len(), 1);
+        assert!(findings[0].message.contains("process.env.VALUE"));
+    }
+
+    #[test]
+    fn trusted_context() {
+        let findings = findings(
+            r#"on: push
+jobs:
+  build:
+    runs-on: ubuntu-latest
+    steps:
+      - run: echo "${{ github.sha }}"
+"#,
+        );
+
+        assert!(findings.is_empty());
+    }
+}
@@ -0,0 +1,152 @@
+//! Detection of workflows and jobs without explicit, minimal `permissions`
+
+use super::{Node, Workflow};
+use crate::findings::{Finding, Severity};
+
+/// Scopes of the `permissions` node granted with write access
+fn write_scopes(permissions: &Node) -> Vec<&str> {
+    permissions
+        .entries()
+        .filter(|(_, access)| access.as_str() == Some("write"))
+        .map(|(scope, _)| scope)
+        .collect()
+}
+
+/// Report workflows and jobs relying on the default `GITHUB_TOKEN` permissions, and requesting
+/// more than needed
+pub(super) fn check(workflow: &Workflow) -> Vec<Finding> {
+    let mut findings = vec![];
+    let top_level = workflow.root.get("permissions");
+
+    if let Some(permissions) = top_level {
+        if permissions.as_str() == Some("write-all") {
+            findings.push(workflow.finding(
+                "write_all_permissions",
+                Severity::Warning,
+                "workflow grants write-all permissions to all its jobs",
+                permissions,
+            ));
+        } else {
+            for scope in write_scopes(permissions) {
+                findings.push(
+                    workflow
+                        .finding(
+                            "workflow_write_permissions",
+                            Severity::Warning,
+                            format!(
+                                "workflow grants {scope}: write to all its jobs, grant it only to the jobs that need it"
+                            ),
+                            permissions,
+                        )
+                        .with_subject(workflow.subject(&[scope])),
+                );
+            }
+        }
+    }
+
+    for (job_id, job) in workflow.jobs() {
+        match job.get("permissions") {
+            Some(permissions) if permissions.as_str() == Some("write-all") => {
+                findings.push(
+                    workflow
+                        .finding(
+                            "write_all_permissions",
+                            Severity::Warning,
+                            format!("job {job_id} requests write-all permissions"),
+                            permissions,
+                        )
+                        .with_subject(workflow.subject(&[job_id])),
+                );
+            }
+            Some(_) => {}
+            None if top_level.is_none() => {
+                findings.push(
+                    workflow
+                        .finding(
+                            "missing_permissions",
+                            Severity::Warning,
+                            format!(
+                                "job {job_id} has no explicit permissions and the workflow sets no default ones, so it gets the default permissions of the repository"
+                            ),
+                            job,
+                        )
+                        .with_subject(workflow.subject(&[job_id])),
+                );
+            }
+            None => {}
+        }
+    }
+
+    findings
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::WorkflowFile;
+    use super::*;
+
+    fn rules(content: &str) -> Vec<String> {
+        check(&WorkflowFile::test(content).parse().unwrap())
+            .into_iter()
+            .map(|finding| finding.rule)
+            .collect()
+    }
+
+    #[test]
+    fn write_all() {
+        assert_eq!(
+            rules(
+                r#"on: push
+permissions: write-all
+jobs:
+  build:
+    runs-on: ubuntu-latest
+    steps:
+      - run: make
+"#
+            ),
+            ["write_all_permissions"]
+        );
+    }
+
+    #[test]
+    fn missing_permissions() {
+        assert_eq!(
+            rules(
+                r#"on: push
+jobs:
+  build:
+    runs-on: ubuntu-latest
+    steps:
+      - run: make
+  test:
+    runs-on: ubuntu-latest
+    permissions:
+      contents: read
+    steps:
+      - run: make test
+"#
+            ),
+            ["missing_permissions"]
+        );
+    }
+
+    #[test]
+    fn workflow_permissions() {
+        assert_eq!(
+            rules(
+                r#"on: push
+permissions:
+  contents: read
+  pull-requests: write
+jobs:
+  build:
+    runs-on: ubuntu-latest
+    steps:
+      - run: make
+"#
+            ),
+            ["workflow_write_permissions"]
+        );
+    }
+}
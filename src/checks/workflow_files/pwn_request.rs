@@ -0,0 +1,171 @@
+//! Detection of privileged workflows checking out and running untrusted code ("pwn requests")
+
+use super::{Node, Workflow};
+use crate::findings::{Finding, Severity};
+
+/// Triggers running in the context of the base repository, with access to secrets and a
+/// read-write token, even for pull requests from forks
+const PRIVILEGED_TRIGGERS: &[&str] = &["pull_request_target", "workflow_run"];
+
+/// Expressions referring to the head of a pull request or of the triggering workflow run
+const UNTRUSTED_REFS: &[&str] = &[
+    "github.event.pull_request.head.sha",
+    "github.event.pull_request.head.ref",
+    "github.event.pull_request.head.repo.full_name",
+    "github.event.pull_request.number",
+    "github.event.number",
+    "github.head_ref",
+    "github.event.workflow_run.head_sha",
+    "github.event.workflow_run.head_branch",
+    "github.event.workflow_run.head_repository.full_name",
+    "refs/pull/",
+];
+
+/// Commands checking out pull requests from `run` steps
+const CHECKOUT_COMMANDS: &[&str] = &["gh pr checkout", "git fetch origin pull/"];
+
+/// `true` if the given value refers to untrusted code
+fn is_untrusted(value: &str) -> bool {
+    UNTRUSTED_REFS
+        .iter()
+        .any(|untrusted| value.contains(untrusted))
+}
+
+/// Node checking out untrusted code in the given step, if any
+fn untrusted_checkout(step: &Node) -> Option<&Node> {
+    if let Some(uses) = step.get("uses") {
+        if uses
+            .as_str()
+            .is_some_and(|uses| uses.starts_with("actions/checkout@"))
+        {
+            return step
+                .get("with")?
+                .entries()
+                .filter(|(key, _)| matches!(*key, "ref" | "repository"))
+                .map(|(_, value)| value)
+                .find(|value| value.as_str().is_some_and(is_untrusted));
+        }
+    }
+
+    step.get("run").filter(|run| {
+        run.as_str().is_some_and(|run| {
+            CHECKOUT_COMMANDS
+                .iter()
+                .any(|command| run.contains(command))
+        })
+    })
+}
+
+/// Report jobs of privileged workflows that check out untrusted code before running other steps
+pub(super) fn check(workflow: &Workflow) -> Vec<Finding> {
+    let triggers: Vec<_> = workflow
+        .triggers()
+        .filter(|trigger| PRIVILEGED_TRIGGERS.contains(trigger))
+        .collect();
+
+    let mut findings = vec![];
+
+    if triggers.is_empty() {
+        return findings;
+    }
+
+    for (job_id, job) in workflow.jobs() {
+        let mut steps = Workflow::steps(job);
+
+        let Some((step_label, checkout)) = steps
+            .by_ref()
+            .find_map(|(label, step)| Some((label, untrusted_checkout(step)?)))
+        else {
+            continue;
+        };
+
+        // Checking out code is only dangerous if something runs it afterwards
+        let Some((next_label, _)) =
+            steps.find(|(_, step)| step.get("run").is_some() || step.get("uses").is_some())
+        else {
+            continue;
+        };
+
+        findings.push(
+            workflow
+                .finding(
+                    "pwn_request",
+                    Severity::Error,
+                    format!(
+                        "workflow triggered by {} checks out untrusted code in step {step_label} of job {job_id}, then runs it in step {next_label}: check out the base repository only, or move the build to a pull_request workflow",
+                        triggers.join(", "),
+                    ),
+                    checkout,
+                )
+                .with_subject(workflow.subject(&[job_id, &step_label])),
+        );
+    }
+
+    findings
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::WorkflowFile;
+    use super::*;
+
+    fn findings(content: &str) -> Vec<Finding> {
+        check(&WorkflowFile::test(content).parse().unwrap())
+    }
+
+    #[test]
+    fn checkout_head_then_run() {
+        let findings = findings(
+            r#"on: pull_request_target
+jobs:
+  build:
+    runs-on: ubuntu-latest
+    steps:
+      - uses: actions/checkout@v4
+        with:
+          ref: ${{ github.event.pull_request.head.sha }}
+      - run: make
+"#,
+        );
+
+        assert_eq!(findings.len(), 1);
+        assert_eq!(findings[0].rule, "pwn_request");
+        assert_eq!(findings[0].location.as_ref().unwrap().line, Some(8));
+    }
+
+    #[test]
+    fn checkout_head_last_step() {
+        let findings = findings(
+            r#"on: pull_request_target
+jobs:
+  build:
+    runs-on: ubuntu-latest
+    steps:
+      - run: echo checking out
+      - uses: actions/checkout@v4
+        with:
+          ref: ${{ github.event.pull_request.head.sha }}
+"#,
+        );
+
+        assert!(findings.is_empty());
+    }
+
+    #[test]
+    fn unprivileged_trigger() {
+        let findings = findings(
+            r#"on: pull_request
+jobs:
+  build:
+    runs-on: ubuntu-latest
+    steps:
+      - uses: actions/checkout@v4
+        with:
+          ref: ${{ github.event.pull_request.head.sha }}
+      - run: make
+"#,
+        );
+
+        assert!(findings.is_empty());
+    }
+}
@@ -0,0 +1,406 @@
+//! Detection of third-party actions not pinned to a commit SHA, and pinning them with `--fix`
+
+use std::collections::HashMap;
+
+use anyhow::anyhow;
+use octocrab::params::repos::Reference;
+use serde::{Deserialize, Serialize};
+use tracing::{info, warn};
+
+use super::{Workflow, WorkflowFile, WorkflowFilesOptions};
+use crate::{
+    checks::CheckCtx,
+    findings::{Finding, Severity},
+};
+
+/// Branch the pinned workflows are pushed to when running with `--fix`
+const FIX_BRANCH: &str = "ghsec/pin-actions";
+
+/// A `uses` reference to an action or reusable workflow that is not pinned to a commit SHA
+struct UnpinnedAction<'w> {
+    /// Job using the action
+    job_id: &'w str,
+    /// Step using the action, `None` for reusable workflows called by the job itself
+    step_label: Option<String>,
+    /// Complete `uses` value
+    uses: &'w str,
+    /// Action or reusable workflow, without the reference
+    action: &'w str,
+    /// Tag or branch the action is pinned to
+    reference: &'w str,
+    /// Line of the `uses` value
+    line: usize,
+}
+
+impl UnpinnedAction<'_> {
+    /// Repository hosting the action
+    fn repository(&self) -> String {
+        self.action
+            .splitn(3, '/')
+            .take(2)
+            .collect::<Vec<_>>()
+            .join("/")
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct Commit {
+    sha: String,
+}
+
+/// Request body for moving a branch to another commit
+#[derive(Debug, Serialize)]
+struct ReferenceUpdate {
+    sha: String,
+    force: bool,
+}
+
+/// `true` if the given reference is a full commit SHA
+fn is_commit_sha(reference: &str) -> bool {
+    reference.len() == 40 && reference.bytes().all(|b| b.is_ascii_hexdigit())
+}
+
+/// Unpinned third-party actions used by the given workflow
+fn unpinned<'w>(workflow: &'w Workflow, options: &WorkflowFilesOptions) -> Vec<UnpinnedAction<'w>> {
+    let mut actions = vec![];
+
+    for (job_id, job) in workflow.jobs() {
+        let uses = job.get("uses").map(|uses| (None, uses)).into_iter().chain(
+            Workflow::steps(job).filter_map(|(label, step)| Some((Some(label), step.get("uses")?))),
+        );
+
+        for (step_label, node) in uses {
+            // Local actions and Docker images are not fetched from other repositories
+            let Some((action, reference)) = node
+                .as_str()
+                .filter(|uses| !uses.starts_with("./") && !uses.starts_with("docker://"))
+                .and_then(|uses| uses.split_once('@'))
+            else {
+                continue;
+            };
+
+            let owner = action.split('/').next().unwrap_or_default();
+            if is_commit_sha(reference)
+                || options
+                    .trusted_action_owners
+                    .iter()
+                    .any(|trusted| trusted.eq_ignore_ascii_case(owner))
+            {
+                continue;
+            }
+
+            actions.push(UnpinnedAction {
+                job_id,
+                step_label,
+                uses: node.as_str().unwrap_or_default(),
+                action,
+                reference,
+                line: node.line,
+            });
+        }
+    }
+
+    actions
+}
+
+/// Report third-party actions and reusable workflows that are not pinned to a commit SHA
+pub(super) fn check(workflow: &Workflow, options: &WorkflowFilesOptions) -> Vec<Finding> {
+    unpinned(workflow, options)
+        .into_iter()
+        .map(|action| {
+            let (scope, subject) = match &action.step_label {
+                Some(step_label) => (
+                    format!("step {step_label} of job {}", action.job_id),
+                    workflow.subject(&[action.job_id, step_label]),
+                ),
+                None => (
+                    format!("job {}", action.job_id),
+                    workflow.subject(&[action.job_id]),
+                ),
+            };
+
+            workflow
+                .finding_at(
+                    "unpinned_action",
+                    Severity::Warning,
+                    format!(
+                        "{scope} uses {}, which is pinned to {} instead of a full commit SHA",
+                        action.uses, action.reference
+                    ),
+                    action.line,
+                )
+                .with_subject(subject)
+                .fixable()
+        })
+        .collect()
+}
+
+/// Rewrite the `uses` value on the given line to the pinned SHA, keeping the reference as a comment
+fn pin_line(line: &str, action: &UnpinnedAction, sha: &str) -> Option<String> {
+    let content = line.trim_end_matches(['\r', '\n']);
+    let ending = &line[content.len()..];
+
+    let start = content.find(action.uses)?;
+    let rest = &content[start + action.uses.len()..];
+
+    let mut pinned = format!("{}{}@{sha}{rest}", &content[..start], action.action);
+    if !rest.contains('#') {
+        pinned.push_str(&format!(" # {}", action.reference));
+    }
+
+    pinned.push_str(ending);
+    Some(pinned)
+}
+
+/// Resolve a reference of a repository to a commit SHA
+async fn resolve_commit(
+    ctx: &CheckCtx<'_>,
+    repository: &str,
+    reference: &str,
+) -> anyhow::Result<String> {
+    let commit: Commit = ctx
+        .gh
+        .get(
+            format!("/repos/{repository}/commits/{reference}"),
+            Option::<()>::None.as_ref(),
+        )
+        .await?;
+
+    Ok(commit.sha)
+}
+
+/// Open a pull request pinning the unpinned third-party actions of the given workflow files
+pub(super) async fn fix(
+    ctx: &CheckCtx<'_>,
+    full_name: &str,
+    default_branch: &str,
+    files: &[WorkflowFile],
+    options: &WorkflowFilesOptions,
+) -> anyhow::Result<()> {
+    let mut resolved: HashMap<(String, String), String> = HashMap::new();
+    let mut updates = vec![];
+
+    for file in files {
+        let Ok(workflow) = file.parse() else {
+            continue;
+        };
+
+        let actions = unpinned(&workflow, options);
+        if actions.is_empty() {
+            continue;
+        }
+
+        let mut lines: Vec<String> = file
+            .content
+            .split_inclusive('\n')
+            .map(ToOwned::to_owned)
+            .collect();
+
+        for action in &actions {
+            let key = (action.repository(), action.reference.to_owned());
+            let sha = match resolved.get(&key) {
+                Some(sha) => sha.clone(),
+                None => {
+                    let sha = resolve_commit(ctx, &key.0, &key.1).await?;
+                    resolved.insert(key, sha.clone());
+                    sha
+                }
+            };
+
+            match lines
+                .get_mut(action.line - 1)
+                .and_then(|line| Some((pin_line(line, action, &sha)?, line)))
+            {
+                Some((pinned, line)) => *line = pinned,
+                None => warn!(
+                    "could not find {} at {}:{}",
+                    action.uses, file.path, action.line
+                ),
+            }
+        }
+
+        updates.push((file, lines.concat()));
+    }
+
+    if updates.is_empty() {
+        return Ok(());
+    }
+
+    let (owner, name) = full_name
+        .split_once('/')
+        .ok_or_else(|| anyhow!("invalid repository name {full_name}"))?;
+    let repos = ctx.gh.repos(owner, name);
+
+    // A branch left by a previous run is reset, so the pinned files are based on the default branch
+    let head = resolve_commit(ctx, full_name, default_branch).await?;
+    match repos
+        .create_ref(&Reference::Branch(FIX_BRANCH.to_owned()), head.clone())
+        .await
+    {
+        Ok(_) => {}
+        Err(octocrab::Error::GitHub { source, .. })
+            if source.message == "Reference already exists" =>
+        {
+            info!("resetting existing branch {FIX_BRANCH} to {default_branch}");
+
+            ctx.gh
+                .patch::<serde_json::Value, _, _>(
+                    format!("/repos/{full_name}/git/refs/heads/{FIX_BRANCH}"),
+                    Some(&ReferenceUpdate {
+                        sha: head,
+                        force: true,
+                    }),
+                )
+                .await?;
+        }
+        Err(err) => return Err(err.into()),
+    }
+
+    for (file, content) in updates {
+        info!("pinning actions in {}", file.path);
+
+        repos
+            .update_file(
+                &file.path,
+                format!("Pin third-party actions in {}", file.path),
+                content,
+                &file.sha,
+            )
+            .branch(FIX_BRANCH)
+            .send()
+            .await?;
+    }
+
+    let existing = ctx
+        .gh
+        .pulls(owner, name)
+        .list()
+        .state(octocrab::params::State::Open)
+        .head(format!("{owner}:{FIX_BRANCH}"))
+        .send()
+        .await?;
+
+    if let Some(pull) = existing.items.first() {
+        info!("updated pull request #{}", pull.number);
+        return Ok(());
+    }
+
+    let pull = ctx
+        .gh
+        .pulls(owner, name)
+        .create(
+            "Pin third-party actions to commit SHAs",
+            FIX_BRANCH,
+            default_branch,
+        )
+        .body(
+            "Third-party actions referenced by a tag or a branch can be changed by their owners \
+             after the fact. This pins them to the commit SHA they currently resolve to, keeping \
+             the original reference as a comment.\n\nOpened by ghsec.",
+        )
+        .send()
+        .await?;
+
+    info!(
+        "opened {}",
+        pull.html_url
+            .map(|url| url.to_string())
+            .unwrap_or_else(|| format!("pull request #{}", pull.number))
+    );
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn action<'w>(uses: &'w str) -> UnpinnedAction<'w> {
+        let (action, reference) = uses.split_once('@').unwrap();
+
+        UnpinnedAction {
+            job_id: "build",
+            step_label: None,
+            uses,
+            action,
+            reference,
+            line: 1,
+        }
+    }
+
+    const SHA: &str = "0123456789abcdef0123456789abcdef01234567";
+
+    #[test]
+    fn pin_plain() {
+        assert_eq!(
+            pin_line(
+                "      - uses: owner/action@v1\n",
+                &action("owner/action@v1"),
+                SHA
+            )
+            .unwrap(),
+            format!("      - uses: owner/action@{SHA} # v1\n")
+        );
+    }
+
+    #[test]
+    fn pin_quoted() {
+        assert_eq!(
+            pin_line(
+                "    uses: \"owner/action@v1\"\n",
+                &action("owner/action@v1"),
+                SHA
+            )
+            .unwrap(),
+            format!("    uses: \"owner/action@{SHA}\" # v1\n")
+        );
+        assert_eq!(
+            pin_line(
+                "    uses: 'owner/repo/path@main'\n",
+                &action("owner/repo/path@main"),
+                SHA
+            )
+            .unwrap(),
+            format!("    uses: 'owner/repo/path@{SHA}' # main\n")
+        );
+    }
+
+    #[test]
+    fn pin_keeps_existing_comment() {
+        assert_eq!(
+            pin_line(
+                "  - uses: owner/action@v1 # pinned later\n",
+                &action("owner/action@v1"),
+                SHA
+            )
+            .unwrap(),
+            format!("  - uses: owner/action@{SHA} # pinned later\n")
+        );
+    }
+
+    #[test]
+    fn pin_crlf() {
+        assert_eq!(
+            pin_line(
+                "  - uses: owner/action@v1\r\n",
+                &action("owner/action@v1"),
+                SHA
+            )
+            .unwrap(),
+            format!("  - uses: owner/action@{SHA} # v1\r\n")
+        );
+    }
+
+    #[test]
+    fn pin_last_line() {
+        assert_eq!(
+            pin_line("  - uses: owner/action@v1", &action("owner/action@v1"), SHA).unwrap(),
+            format!("  - uses: owner/action@{SHA} # v1")
+        );
+    }
+
+    #[test]
+    fn pin_missing() {
+        assert!(pin_line("  - run: make\n", &action("owner/action@v1"), SHA).is_none());
+    }
+}
@@ -0,0 +1,63 @@
+//! Detection of workflows re-enabling the deprecated `set-env` and `add-path` commands
+
+use super::{Node, Workflow};
+use crate::findings::{Finding, Severity};
+
+/// Environment variable re-enabling unsecure workflow commands
+const VARIABLE: &str = "ACTIONS_ALLOW_UNSECURE_COMMANDS";
+
+/// Node enabling unsecure commands in the `env` of the given node, if any
+fn enabled(node: &Node) -> Option<&Node> {
+    node.get("env")?.get(VARIABLE).filter(|value| {
+        value
+            .as_str()
+            .is_some_and(|v| v.eq_ignore_ascii_case("true"))
+    })
+}
+
+/// Report workflows, jobs and steps setting `ACTIONS_ALLOW_UNSECURE_COMMANDS`
+pub(super) fn check(workflow: &Workflow) -> Vec<Finding> {
+    let mut findings = vec![];
+    let message = |scope: &str| format!("{VARIABLE} is enabled for {scope}");
+
+    if let Some(node) = enabled(&workflow.root) {
+        findings.push(workflow.finding(
+            "unsecure_commands",
+            Severity::Warning,
+            message("the whole workflow"),
+            node,
+        ));
+    }
+
+    for (job_id, job) in workflow.jobs() {
+        if let Some(node) = enabled(job) {
+            findings.push(
+                workflow
+                    .finding(
+                        "unsecure_commands",
+                        Severity::Warning,
+                        message(&format!("job {job_id}")),
+                        node,
+                    )
+                    .with_subject(workflow.subject(&[job_id])),
+            );
+        }
+
+        for (step_label, step) in Workflow::steps(job) {
+            if let Some(node) = enabled(step) {
+                findings.push(
+                    workflow
+                        .finding(
+                            "unsecure_commands",
+                            Severity::Warning,
+                            message(&format!("step {step_label} of job {job_id}")),
+                            node,
+                        )
+                        .with_subject(workflow.subject(&[job_id, &step_label])),
+                );
+            }
+        }
+    }
+
+    findings
+}
@@ -0,0 +1,270 @@
+//! Minimal YAML document model keeping track of line numbers
+//!
+//! Workflow findings are reported with the line they refer to, which regular YAML deserializers
+//! do not keep track of. This model is built from the events of the YAML parser instead.
+
+use std::collections::HashMap;
+
+use yaml_rust2::{
+    parser::{Event, MarkedEventReceiver, Parser},
+    scanner::{Marker, ScanError, TScalarStyle},
+};
+
+/// Value of a YAML node
+#[derive(Debug, Clone)]
+pub(crate) enum Value {
+    Null,
+    Scalar(String),
+    Sequence(Vec<Node>),
+    Mapping(Vec<(Node, Node)>),
+}
+
+/// A YAML node and the line it starts at
+#[derive(Debug, Clone)]
+pub(crate) struct Node {
+    pub value: Value,
+    pub line: usize,
+}
+
+impl Node {
+    /// Value of the given key, if this node is a mapping containing it
+    pub fn get(&self, key: &str) -> Option<&Node> {
+        self.entries()
+            .find(|(name, _)| *name == key)
+            .map(|(_, value)| value)
+    }
+
+    /// Entries of this node with scalar keys, if this node is a mapping
+    pub fn entries(&self) -> impl Iterator<Item = (&str, &Node)> {
+        let entries = match &self.value {
+            Value::Mapping(entries) => entries.as_slice(),
+            _ => &[],
+        };
+
+        entries
+            .iter()
+            .filter_map(|(key, value)| Some((key.as_str()?, value)))
+    }
+
+    /// Items of this node, if this node is a sequence
+    pub fn items(&self) -> &[Node] {
+        match &self.value {
+            Value::Sequence(items) => items,
+            _ => &[],
+        }
+    }
+
+    /// Value of this node, if it is a scalar
+    pub fn as_str(&self) -> Option<&str> {
+        match &self.value {
+            Value::Scalar(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    /// Number of nodes in this tree, including this one
+    fn size(&self) -> usize {
+        1 + match &self.value {
+            Value::Null | Value::Scalar(_) => 0,
+            Value::Sequence(items) => items.iter().map(Node::size).sum(),
+            Value::Mapping(entries) => entries
+                .iter()
+                .map(|(key, value)| key.size() + value.size())
+                .sum(),
+        }
+    }
+}
+
+/// Maximum number of nodes copied when resolving aliases, to reject documents nesting aliases to
+/// expand into huge trees
+const MAX_ALIAS_NODES: usize = 10_000;
+
+/// Event receiver building the first document of a YAML stream
+#[derive(Default)]
+struct Loader {
+    stack: Vec<(Node, Option<Node>, usize)>,
+    anchors: HashMap<usize, Node>,
+    root: Option<Node>,
+    /// Number of nodes copied when resolving aliases so far
+    alias_nodes: usize,
+    /// Error to report once the parser is done
+    error: Option<ScanError>,
+}
+
+impl Loader {
+    fn insert(&mut self, node: Node) {
+        match self.stack.last_mut() {
+            Some((parent, key, _)) => match &mut parent.value {
+                Value::Sequence(items) => items.push(node),
+                Value::Mapping(entries) => match key.take() {
+                    Some(key) => entries.push((key, node)),
+                    None => *key = Some(node),
+                },
+                Value::Null | Value::Scalar(_) => unreachable!("scalars have no children"),
+            },
+            None => {
+                self.root.get_or_insert(node);
+            }
+        }
+    }
+}
+
+impl MarkedEventReceiver for Loader {
+    fn on_event(&mut self, ev: Event, mark: Marker) {
+        let line = mark.line();
+
+        match ev {
+            Event::Scalar(value, style, anchor, _) => {
+                let value = match (style, value.as_str()) {
+                    (TScalarStyle::Plain, "" | "~" | "null") => Value::Null,
+                    _ => Value::Scalar(value),
+                };
+                let node = Node { value, line };
+
+                if anchor > 0 {
+                    self.anchors.insert(anchor, node.clone());
+                }
+
+                self.insert(node);
+            }
+            Event::SequenceStart(anchor, _) => {
+                let node = Node {
+                    value: Value::Sequence(vec![]),
+                    line,
+                };
+                self.stack.push((node, None, anchor));
+            }
+            Event::MappingStart(anchor, _) => {
+                let node = Node {
+                    value: Value::Mapping(vec![]),
+                    line,
+                };
+                self.stack.push((node, None, anchor));
+            }
+            Event::SequenceEnd | Event::MappingEnd => {
+                if let Some((node, _, anchor)) = self.stack.pop() {
+                    if anchor > 0 {
+                        self.anchors.insert(anchor, node.clone());
+                    }
+
+                    self.insert(node);
+                }
+            }
+            Event::Alias(anchor) => {
+                let node = self.anchors.get(&anchor).cloned().unwrap_or(Node {
+                    value: Value::Null,
+                    line,
+                });
+
+                self.alias_nodes += node.size();
+                if self.alias_nodes > MAX_ALIAS_NODES {
+                    self.error
+                        .get_or_insert_with(|| ScanError::new(mark, "too many aliased nodes"));
+                    return;
+                }
+
+                self.insert(node);
+            }
+            Event::Nothing
+            | Event::StreamStart
+            | Event::StreamEnd
+            | Event::DocumentStart
+            | Event::DocumentEnd => {}
+        }
+    }
+}
+
+/// Parse the first document of a YAML stream
+pub(crate) fn parse(source: &str) -> Result<Node, ScanError> {
+    let mut loader = Loader::default();
+    Parser::new_from_str(source).load(&mut loader, false)?;
+
+    if let Some(error) = loader.error {
+        return Err(error);
+    }
+
+    Ok(loader.root.unwrap_or(Node {
+        value: Value::Null,
+        line: 1,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn mapping_lines() {
+        let root = parse("name: ci\non:\n  push:\n\njobs:\n  build:\n    runs-on: ubuntu-latest\n")
+            .unwrap();
+
+        assert_eq!(root.get("name").unwrap().as_str(), Some("ci"));
+        assert_eq!(root.get("name").unwrap().line, 1);
+        assert_eq!(root.get("on").unwrap().line, 3);
+        assert!(matches!(
+            root.get("on").unwrap().get("push").unwrap().value,
+            Value::Null
+        ));
+
+        let build = root.get("jobs").unwrap().get("build").unwrap();
+        assert_eq!(build.line, 7);
+        assert_eq!(build.get("runs-on").unwrap().line, 7);
+    }
+
+    #[test]
+    fn sequence_lines() {
+        let root = parse(
+            "steps:\n  - uses: actions/checkout@v4\n  - run: |\n      make\n      make test\n",
+        )
+        .unwrap();
+        let steps = root.get("steps").unwrap().items();
+
+        assert_eq!(steps.len(), 2);
+        assert_eq!(steps[0].get("uses").unwrap().line, 2);
+        // Block scalars start on the line after their indicator
+        assert_eq!(steps[1].get("run").unwrap().line, 4);
+        assert_eq!(
+            steps[1].get("run").unwrap().as_str(),
+            Some("make\nmake test\n")
+        );
+    }
+
+    #[test]
+    fn quoted_scalars() {
+        let root = parse("a: \"null\"\nb: null\nc: ''\n").unwrap();
+
+        assert_eq!(root.get("a").unwrap().as_str(), Some("null"));
+        assert!(matches!(root.get("b").unwrap().value, Value::Null));
+        assert_eq!(root.get("c").unwrap().as_str(), Some(""));
+    }
+
+    #[test]
+    fn aliases() {
+        let root = parse("env: &env\n  A: 1\njob:\n  env: *env\n").unwrap();
+        let env = root.get("job").unwrap().get("env").unwrap();
+
+        assert_eq!(env.get("A").unwrap().as_str(), Some("1"));
+        // Aliased nodes keep the line of their anchor
+        assert_eq!(env.get("A").unwrap().line, 2);
+    }
+
+    #[test]
+    fn nested_aliases_are_capped() {
+        let mut source = String::from("a0: &a0 [x, x, x, x, x, x, x, x, x, x]\n");
+        for i in 1..10 {
+            let prev = i - 1;
+            source.push_str(&format!(
+                "a{i}: &a{i} [*a{prev}, *a{prev}, *a{prev}, *a{prev}, *a{prev}, *a{prev}, *a{prev}, *a{prev}, *a{prev}, *a{prev}]\n"
+            ));
+        }
+
+        let err = parse(&source).unwrap_err();
+        assert_eq!(err.info(), "too many aliased nodes");
+    }
+
+    #[test]
+    fn empty_document() {
+        let root = parse("").unwrap();
+        assert!(matches!(root.value, Value::Null));
+    }
+}
@@ -0,0 +1,321 @@
+//! Configuration file support
+//!
+//! The configuration file selects the checks to run and sets their options. Options are given as
+//! one table per check, and can be overridden for repositories matching a glob pattern. Flags
+//! passed on the command line take precedence over the configuration file. The configuration file
+//! also holds the [suppressions](crate::suppressions) for known findings.
+
+use std::path::{Path, PathBuf};
+
+use anyhow::Context;
+use serde::{de::Error as _, Deserialize, Deserializer};
+
+use crate::{
+    args::{Args, CheckRunRequest},
+    checks::{
+        AllowedActionsOptions, BranchProtectionsOptions, Checks, CollaboratorsOptions,
+        DependabotOptions, DeployKeysOptions, OrganizationSecretsOptions, RepositorySecretsOptions,
+        WebhooksOptions, WorkflowFilesOptions,
+    },
+    suppressions::Suppression,
+};
+
+/// Name of the configuration file loaded from the current directory, if it exists
+pub const DEFAULT_CONFIG_FILE: &str = "ghsec.toml";
+
+/// Options for individual checks, keyed by check name
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct CheckOptions {
+    pub allowed_actions: AllowedActionsOptions,
+    pub branch_protections: BranchProtectionsOptions,
+    pub collaborators: CollaboratorsOptions,
+    pub dependabot: DependabotOptions,
+    pub deploy_keys: DeployKeysOptions,
+    pub organization_secrets: OrganizationSecretsOptions,
+    pub repository_secrets: RepositorySecretsOptions,
+    pub webhooks: WebhooksOptions,
+    pub workflow_files: WorkflowFilesOptions,
+}
+
+/// Options overridden for repositories matching a pattern
+#[derive(Debug, Deserialize)]
+struct Override {
+    /// Repositories this override applies to. Supports globs.
+    #[serde(deserialize_with = "deserialize_pattern")]
+    repositories: glob::Pattern,
+    /// Checks to run on matching repositories
+    checks: Option<CheckRunRequest>,
+    /// Check options for matching repositories
+    #[serde(flatten)]
+    options: toml::Table,
+}
+
+/// Parsed configuration file
+#[derive(Debug, Default, Deserialize)]
+pub struct Config {
+    /// Path to the file this configuration was loaded from
+    #[serde(skip)]
+    pub path: Option<PathBuf>,
+    /// Checks to run
+    checks: Option<CheckRunRequest>,
+    /// Per-repository overrides, applied in order
+    #[serde(default)]
+    overrides: Vec<Override>,
+    /// Suppressed findings
+    #[serde(default)]
+    pub suppressions: Vec<Suppression>,
+    /// Check options for all repositories
+    #[serde(flatten)]
+    options: toml::Table,
+}
+
+impl Config {
+    /// Load the configuration file specified on the command line, or the default one if it exists
+    pub fn load(args: &Args) -> anyhow::Result<Self> {
+        match &args.config {
+            Some(path) => Self::from_path(path),
+            None if Path::new(DEFAULT_CONFIG_FILE).exists() => {
+                Self::from_path(Path::new(DEFAULT_CONFIG_FILE))
+            }
+            None => Ok(Self::default()),
+        }
+    }
+
+    fn from_path(path: &Path) -> anyhow::Result<Self> {
+        let source = std::fs::read_to_string(path)
+            .with_context(|| format!("failed to read {}", path.display()))?;
+
+        let mut config: Self =
+            toml::from_str(&source).with_context(|| format!("invalid {}", path.display()))?;
+        config.path = Some(path.to_owned());
+
+        config
+            .validate()
+            .with_context(|| format!("invalid {}", path.display()))?;
+
+        Ok(config)
+    }
+
+    /// Ensure the options for all repositories and for every override are valid
+    pub fn validate(&self) -> anyhow::Result<()> {
+        CheckOptions::deserialize(self.options.clone())?;
+
+        for item in &self.overrides {
+            let mut options = self.options.clone();
+            merge(&mut options, &item.options);
+            CheckOptions::deserialize(options).with_context(|| {
+                format!("invalid override for repositories {}", item.repositories)
+            })?;
+        }
+
+        for suppression in &self.suppressions {
+            suppression.validate()?;
+        }
+
+        Ok(())
+    }
+
+    /// Overrides applying to the given repository
+    fn overrides_for<'s>(&'s self, repository: &'s str) -> impl Iterator<Item = &'s Override> {
+        self.overrides
+            .iter()
+            .filter(move |item| matches_repository(&item.repositories, repository))
+    }
+
+    /// Checks to run on all repositories, and on the account or organizations
+    pub fn checks(&self, args: &Args) -> CheckRunRequest {
+        args.checks
+            .clone()
+            .or_else(|| self.checks.clone())
+            .unwrap_or_default()
+    }
+
+    /// Checks that may run on any repository, or on the account or organizations
+    pub fn all_checks<'s>(&'s self, args: &Args) -> impl Iterator<Item = Checks> + 's {
+        let overrides = args.checks.is_none().then_some(&self.overrides);
+
+        self.checks(args).into_iter().chain(
+            overrides
+                .into_iter()
+                .flatten()
+                .filter_map(|item| item.checks.clone())
+                .flatten(),
+        )
+    }
+
+    /// Checks to run on the given repository
+    pub fn checks_for(&self, args: &Args, repository: &str) -> CheckRunRequest {
+        args.checks
+            .clone()
+            .or_else(|| {
+                self.overrides_for(repository)
+                    .filter_map(|item| item.checks.clone())
+                    .last()
+            })
+            .or_else(|| self.checks.clone())
+            .unwrap_or_default()
+    }
+
+    /// `true` if repository checks need to run on at least one repository
+    pub fn has_repository_checks(&self, args: &Args) -> bool {
+        match &args.checks {
+            Some(checks) => checks.has_repository_checks(),
+            None => {
+                self.checks(args).has_repository_checks()
+                    || self.overrides.iter().any(|item| {
+                        item.checks
+                            .as_ref()
+                            .is_some_and(CheckRunRequest::has_repository_checks)
+                    })
+            }
+        }
+    }
+
+    /// Check options for account checks
+    pub fn options(&self, args: &Args) -> anyhow::Result<CheckOptions> {
+        Self::resolve(self.options.clone(), args)
+    }
+
+    /// Check options for the given repository
+    pub fn options_for(&self, args: &Args, repository: &str) -> anyhow::Result<CheckOptions> {
+        let mut options = self.options.clone();
+        for item in self.overrides_for(repository) {
+            merge(&mut options, &item.options);
+        }
+
+        Self::resolve(options, args)
+    }
+
+    fn resolve(options: toml::Table, args: &Args) -> anyhow::Result<CheckOptions> {
+        let mut options = CheckOptions::deserialize(options)?;
+
+        if let Some(warn_secret_names) = &args.repository_secrets_warn_secret_names {
+            options.repository_secrets.warn_secret_names = warn_secret_names.clone();
+        }
+
+        Ok(options)
+    }
+}
+
+/// Check if a pattern matches the full name of a repository, or its name without the owner
+pub fn matches_repository(pattern: &glob::Pattern, full_name: &str) -> bool {
+    pattern.matches(full_name)
+        || full_name
+            .split_once('/')
+            .is_some_and(|(_, name)| pattern.matches(name))
+}
+
+/// Recursively merge the `other` table into `base`
+fn merge(base: &mut toml::Table, other: &toml::Table) {
+    for (key, value) in other {
+        match (base.get_mut(key), value) {
+            (Some(toml::Value::Table(base)), toml::Value::Table(other)) => merge(base, other),
+            _ => {
+                base.insert(key.clone(), value.clone());
+            }
+        }
+    }
+}
+
+/// Deserialize a unix-style glob from a string
+pub(crate) fn deserialize_pattern<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<glob::Pattern, D::Error> {
+    let pattern = String::deserialize(deserializer)?;
+    glob::Pattern::new(&pattern).map_err(D::Error::custom)
+}
+
+/// Deserialize a regular expression from a string
+pub(crate) fn deserialize_regex<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<regex::Regex, D::Error> {
+    let regex = String::deserialize(deserializer)?;
+    regex::Regex::new(&regex).map_err(D::Error::custom)
+}
+
+#[cfg(test)]
+mod tests {
+    use clap::Parser;
+
+    use super::*;
+
+    fn args() -> Args {
+        Args::parse_from(["ghsec", "--github-token", "token"])
+    }
+
+    fn config(toml: &str) -> Config {
+        let config: Config = toml::from_str(toml).unwrap();
+        config.validate().unwrap();
+        config
+    }
+
+    const CONFIG: &str = r#"
+[deploy_keys]
+max_age_days = 100
+
+[[overrides]]
+repositories = "octocat/*"
+deploy_keys = { max_unused_days = 10 }
+
+[[overrides]]
+repositories = "hello-*"
+deploy_keys = { max_unused_days = 20 }
+"#;
+
+    #[test]
+    fn options_without_overrides() {
+        let options = config(CONFIG).options_for(&args(), "github/docs").unwrap();
+
+        assert_eq!(options.deploy_keys.max_age_days, 100);
+        assert_eq!(options.deploy_keys.max_unused_days, 90);
+    }
+
+    #[test]
+    fn overrides_are_merged() {
+        let options = config(CONFIG).options_for(&args(), "octocat/docs").unwrap();
+
+        assert_eq!(options.deploy_keys.max_age_days, 100);
+        assert_eq!(options.deploy_keys.max_unused_days, 10);
+    }
+
+    #[test]
+    fn later_overrides_win() {
+        let options = config(CONFIG)
+            .options_for(&args(), "octocat/hello-world")
+            .unwrap();
+
+        assert_eq!(options.deploy_keys.max_age_days, 100);
+        assert_eq!(options.deploy_keys.max_unused_days, 20);
+    }
+
+    #[test]
+    fn invalid_override() {
+        let config: Config = toml::from_str(
+            r#"
+[[overrides]]
+repositories = "octocat/*"
+deploy_keys = { max_unused_days = "never" }
+"#,
+        )
+        .unwrap();
+
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn all_checks_include_overrides() {
+        let config = config(
+            r#"
+checks = ["deploy_keys"]
+
+[[overrides]]
+repositories = "octocat/*"
+checks = ["deploy_keys", "webhooks"]
+"#,
+        );
+        let checks: Vec<_> = config.all_checks(&args()).map(|c| c.to_string()).collect();
+
+        assert_eq!(checks, ["deploy_keys", "deploy_keys", "webhooks"]);
+    }
+}
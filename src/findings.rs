@@ -0,0 +1,190 @@
+//! Structured results reported by checks
+
+use serde::{Deserialize, Serialize};
+use tracing::{error, info, warn};
+
+/// Severity of a finding
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    Serialize,
+    Deserialize,
+    strum::Display,
+    strum::EnumString,
+    clap::ValueEnum,
+)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum Severity {
+    /// Informational finding, or reminder for a setting that ghsec cannot verify by itself
+    Info,
+    /// Potential security issue that should be reviewed
+    Warning,
+    /// Security issue
+    Error,
+}
+
+/// Location of a finding within a file of a repository
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FindingLocation {
+    /// Path to the file, relative to the root of the repository
+    pub path: String,
+    /// Line number in the file, starting at 1
+    pub line: Option<usize>,
+}
+
+/// A problem detected by a check
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Finding {
+    /// Identifier of the check that reported this finding
+    pub check: String,
+    /// Full name of the repository this finding is about, if any
+    pub repository: Option<String>,
+    /// Login of the organization this finding is about, if any
+    pub organization: Option<String>,
+    /// Identifier of the rule that triggered this finding, unique within a check
+    pub rule: String,
+    /// Object this finding is about within the repository, such as a secret name
+    pub subject: Option<String>,
+    /// File and line this finding is about within the repository, if any
+    pub location: Option<FindingLocation>,
+    /// Severity of this finding
+    pub severity: Severity,
+    /// Human-readable description of the problem
+    pub message: String,
+    /// Link to the settings page where the problem can be reviewed
+    pub link: Option<String>,
+    /// `true` if running with `--fix` can fix the problem
+    pub fixable: bool,
+    /// Justification for suppressing this finding, if it is suppressed
+    pub suppressed: Option<String>,
+}
+
+impl Finding {
+    /// Create a new finding for the given rule.
+    ///
+    /// The check and repository are filled in by the check runner.
+    pub fn new(rule: impl Into<String>, severity: Severity, message: impl Into<String>) -> Self {
+        Self {
+            check: String::new(),
+            repository: None,
+            organization: None,
+            rule: rule.into(),
+            subject: None,
+            location: None,
+            severity,
+            message: message.into(),
+            link: None,
+            fixable: false,
+            suppressed: None,
+        }
+    }
+
+    /// Set the link to the settings page for this finding
+    pub fn with_link(mut self, link: impl Into<String>) -> Self {
+        self.link = Some(link.into());
+        self
+    }
+
+    /// Set the organization this finding is about
+    pub fn with_organization(mut self, organization: impl Into<String>) -> Self {
+        self.organization = Some(organization.into());
+        self
+    }
+
+    /// Name of the repository or organization this finding is about, if any
+    pub fn target(&self) -> Option<&str> {
+        self.repository.as_deref().or(self.organization.as_deref())
+    }
+
+    /// Set the object this finding is about
+    pub fn with_subject(mut self, subject: impl Into<String>) -> Self {
+        self.subject = Some(subject.into());
+        self
+    }
+
+    /// Set the file and line this finding is about
+    pub fn with_location(mut self, path: impl Into<String>, line: Option<usize>) -> Self {
+        self.location = Some(FindingLocation {
+            path: path.into(),
+            line,
+        });
+        self
+    }
+
+    /// Mark this finding as fixable using `--fix`
+    pub fn fixable(mut self) -> Self {
+        self.fixable = true;
+        self
+    }
+
+    /// Set the check that reported this finding
+    pub fn with_check(mut self, check: impl ToString) -> Self {
+        self.check = check.to_string();
+        self
+    }
+
+    /// Set the repository this finding is about
+    pub fn with_repository(mut self, repository: impl Into<String>) -> Self {
+        self.repository = Some(repository.into());
+        self
+    }
+
+    /// Identifier of this finding, stable across runs as long as the same problem is detected
+    pub fn fingerprint(&self) -> String {
+        format!(
+            "{}:{}:{}:{}",
+            self.target().unwrap_or_default(),
+            self.check,
+            self.rule,
+            self.subject.as_deref().unwrap_or_default()
+        )
+    }
+
+    /// Emit this finding as a tracing event
+    pub fn log(&self) {
+        let Self {
+            check,
+            repository,
+            organization,
+            rule,
+            subject,
+            location,
+            severity,
+            message,
+            link,
+            fixable,
+            suppressed: _,
+        } = self;
+
+        let path = location.as_ref().map(|location| location.path.as_str());
+        let line = location.as_ref().and_then(|location| location.line);
+
+        match severity {
+            Severity::Info => {
+                info!(
+                    check,
+                    repository, organization, rule, subject, path, line, link, fixable, "{message}"
+                )
+            }
+            Severity::Warning => {
+                warn!(
+                    check,
+                    repository, organization, rule, subject, path, line, link, fixable, "{message}"
+                )
+            }
+            Severity::Error => {
+                error!(
+                    check,
+                    repository, organization, rule, subject, path, line, link, fixable, "{message}"
+                )
+            }
+        }
+    }
+}
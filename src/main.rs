@@ -35,23 +35,112 @@
 //!
 //! # You can also specify repositories to check using a unix-style glob
 //! ghsec 'workflows-*'
+//!
+//! # Check the repositories and settings of organizations instead of your own repositories
+//! ghsec --org my-org --org my-other-org
+//!
+//! # Write the findings as a SARIF log, e.g. for uploading to a security dashboard
+//! ghsec --sarif ghsec.sarif
+//!
+//! # Exit with a non-zero code if any warning or error is found, e.g. in CI
+//! ghsec --fail-on warning
+//!
+//! # Record the current findings, and only report new findings in later runs
+//! ghsec --write-baseline ghsec-baseline.json
+//! ghsec --baseline ghsec-baseline.json --fail-on warning
+//! ```
+//!
+//! ### Exit codes
+//!
+//! - `0`: all checks ran, and no findings at or above the `--fail-on` severity were reported
+//! - `1`: findings at or above the `--fail-on` severity were reported
+//! - `2`: running checks or fixing findings failed, findings may be incomplete
+//!
+//! ## Configuration
+//!
+//! Checks and their options can be configured using a `ghsec.toml` file in the current directory,
+//! or the file given with `--config`. Flags passed on the command line take precedence over the
+//! configuration file.
+//!
+//! ```toml
+//! # Checks to run, defaults to "all"
+//! checks = ["branch_protections", "default_workflow_permissions", "repository_secrets"]
+//!
+//! # Options for each check are set in a table named after the check
+//! [branch_protections]
+//! required_approving_review_count = 1
+//! required_status_checks = ["ci"]
+//!
+//! [repository_secrets]
+//! warn_secret_names = "^GH_(TOKEN|PAT)$"
+//! max_sensitive_age_days = 90
+//!
+//! # Overrides apply to repositories matching a unix-style glob, in order
+//! [[overrides]]
+//! repositories = "mirror-*"
+//! checks = ["repository_secrets"]
+//!
+//! [overrides.repository_secrets]
+//! warn_secret_names = "^(GH_PAT|NPM_TOKEN)$"
+//!
+//! # Suppressions silence known findings, and must be justified
+//! [[suppressions]]
+//! repositories = "mirror-*"
+//! check = "repository_secrets"
+//! # Optional, suppresses all the findings of the check if omitted
+//! rule = "sensitive_secret"
+//! reason = "mirrors need a token to push to the upstream repository"
+//! # Optional, matching findings are reported again after this date
+//! expires = 2025-06-30
 //! ```
 //!
+//! The configuration file can be checked using `ghsec config validate`.
+//!
 //! ## Supported checks
 //!
+//! - [`allowed_actions`](https://alixinne.github.io/ghsec/ghsec/checks/allowed_actions/index.html):
+//!   check which GitHub Actions repositories allow
 //! - [`branch_protections`](https://alixinne.github.io/ghsec/ghsec/checks/branch_protections/index.html):
-//! check branch protection settings
+//!   check branch protection settings
 //! - [`code_review_limits`](https://alixinne.github.io/ghsec/ghsec/checks/code_review_limits/index.html):
-//! check account settings for code review limits
+//!   check account settings for code review limits
+//! - [`code_scanning`](https://alixinne.github.io/ghsec/ghsec/checks/code_scanning/index.html):
+//!   check code scanning default setup
+//! - [`collaborators`](https://alixinne.github.io/ghsec/ghsec/checks/collaborators/index.html):
+//!   audit collaborators and pending invitations
 //! - [`default_workflow_permissions`](https://alixinne.github.io/ghsec/ghsec/checks/default_worfklow_permissions/index.html):
-//! use secure defaults for "Default Workflow Permissions"
+//!   use secure defaults for "Default Workflow Permissions"
+//! - [`dependabot`](https://alixinne.github.io/ghsec/ghsec/checks/dependabot/index.html):
+//!   check Dependabot alerts and security updates
+//! - [`deploy_keys`](https://alixinne.github.io/ghsec/ghsec/checks/deploy_keys/index.html):
+//!   audit deploy keys with write access, old or unused
 //! - [`fork_pull_request_workflows`](https://alixinne.github.io/ghsec/ghsec/checks/fork_pull_request_workflows/index.html):
-//! check repository settings for public fork pull request workflow runs
+//!   check repository settings for public fork pull request workflow runs
+//! - [`member_privileges`](https://alixinne.github.io/ghsec/ghsec/checks/member_privileges/index.html):
+//!   check organization settings for member privileges
+//! - [`organization_default_workflow_permissions`](https://alixinne.github.io/ghsec/ghsec/checks/default_worfklow_permissions/index.html):
+//!   use secure defaults for "Default Workflow Permissions" in organizations
+//! - [`organization_secrets`](https://alixinne.github.io/ghsec/ghsec/checks/organization_secrets/index.html):
+//!   list organizations containing GitHub Actions secrets
+//! - [`repository_rulesets`](https://alixinne.github.io/ghsec/ghsec/checks/repository_rulesets/index.html):
+//!   check rulesets applying to the default branch
 //! - [`repository_secrets`](https://alixinne.github.io/ghsec/ghsec/checks/repository_secrets/index.html):
-//! list repositories containing GitHub Actions secrets
+//!   list repositories containing GitHub Actions secrets
+//! - [`secret_scanning`](https://alixinne.github.io/ghsec/ghsec/checks/secret_scanning/index.html):
+//!   check secret scanning and push protection settings
+//! - [`self_hosted_runners`](https://alixinne.github.io/ghsec/ghsec/checks/self_hosted_runners/index.html):
+//!   check public repositories for self-hosted runners
+//! - [`vulnerability_reporting`](https://alixinne.github.io/ghsec/ghsec/checks/vulnerability_reporting/index.html):
+//!   check how vulnerabilities can be reported on public repositories
+//! - [`webhooks`](https://alixinne.github.io/ghsec/ghsec/checks/webhooks/index.html):
+//!   audit webhook URLs, TLS verification, secrets and failures
+//! - [`workflow_files`](https://alixinne.github.io/ghsec/ghsec/checks/workflow_files/index.html):
+//!   analyze GitHub Actions workflow files for dangerous patterns
 
-use std::str::FromStr;
+use std::{fs::File, process::ExitCode, str::FromStr};
 
+use anyhow::anyhow;
+use chrono::Utc;
 use clap::Parser;
 use futures_util::{stream::FuturesUnordered, StreamExt, TryStreamExt};
 use octocrab::{models::Repository, Octocrab};
@@ -60,61 +149,147 @@ use tracing::{debug, error, info, level_filters::LevelFilter};
 use tracing_subscriber::{filter::Directive, EnvFilter};
 
 mod args;
-use args::Args;
+use args::{Args, Command, ConfigCommand};
+
+pub mod config;
+use config::Config;
+
+mod baseline;
+use baseline::Baseline;
 
 pub mod checks;
-use checks::{AccountCheck, CheckCtx, Checks, RepositoryCheck};
+use checks::{AccountCheck, CheckCtx, Checks, FixError, OrganizationCheck, RepositoryCheck};
+
+pub mod findings;
+use findings::Finding;
+
+pub mod suppressions;
+
+mod sarif;
+
+/// Findings reported on a repository, account or organization
+#[derive(Default)]
+struct Results {
+    /// Findings reported by the checks that succeeded
+    findings: Vec<Finding>,
+    /// `true` if at least one check failed
+    failed: bool,
+}
+
+impl Results {
+    /// Record the result of running a check, logging its error if it failed. The findings of
+    /// the check are passed through `scope`, to record what they were reported on.
+    fn record(
+        &mut self,
+        check: &impl std::fmt::Display,
+        result: anyhow::Result<Vec<Finding>>,
+        scope: impl Fn(Finding) -> Finding,
+    ) {
+        match result.map_err(|err| err.downcast::<FixError>()) {
+            Ok(findings) => self.findings.extend(findings.into_iter().map(scope)),
+            // Findings are still reported when fixing them failed
+            Err(Ok(FixError { findings, errors })) => {
+                for err in errors {
+                    error!(check = %check, ?err, "error fixing findings");
+                }
+
+                self.findings.extend(findings.into_iter().map(scope));
+                self.failed = true;
+            }
+            Err(Err(err)) => {
+                error!(check = %check, ?err, "error running check");
+                self.failed = true;
+            }
+        }
+    }
+}
 
 #[tracing::instrument(name="repository", level="info", skip_all, fields(repository = repository.full_name.as_ref().unwrap()))]
-async fn process_repo<'c>(ctx: &'c CheckCtx<'c>, repository: Repository) -> anyhow::Result<()> {
-    for check in ctx.args.checks.clone().into_iter() {
+async fn process_repo<'c>(
+    ctx: &'c CheckCtx<'c>,
+    repository: Repository,
+) -> anyhow::Result<Results> {
+    let full_name = repository
+        .full_name
+        .clone()
+        .unwrap_or(repository.name.clone());
+    let ctx = &ctx.for_repository(&full_name)?;
+    let mut results = Results::default();
+
+    for check in ctx.config.checks_for(ctx.args, &full_name) {
         if let Checks::Repository(check) = check {
             debug!(check = %check, "running check");
-            check.run(ctx, &repository).await?;
+            let result = check.run(ctx, &repository).await;
+            results.record(&check, result, |finding| {
+                finding.with_check(&check).with_repository(&full_name)
+            });
         }
     }
 
-    Ok(())
+    Ok(results)
 }
 
 #[tracing::instrument(name = "account", level = "info", skip_all)]
-async fn process_account<'c>(ctx: &'c CheckCtx<'c>) -> anyhow::Result<()> {
-    for check in ctx.args.checks.clone().into_iter() {
+async fn process_account<'c>(ctx: &'c CheckCtx<'c>) -> anyhow::Result<Results> {
+    let mut results = Results::default();
+
+    for check in ctx.config.checks(ctx.args) {
         if let Checks::Account(check) = check {
             debug!(check = %check, "running check");
-            check.run(ctx).await?;
+            let result = check.run(ctx).await;
+            results.record(&check, result, |finding| finding.with_check(&check));
         }
     }
 
-    Ok(())
+    Ok(results)
 }
 
-#[tokio::main(worker_threads = 4)]
-async fn main() -> anyhow::Result<()> {
-    // Load variables from .env
-    dotenv::dotenv().ok();
+#[tracing::instrument(name = "organization", level = "info", skip_all, fields(organization = organization))]
+async fn process_org<'c>(ctx: &'c CheckCtx<'c>, organization: &str) -> anyhow::Result<Results> {
+    let ctx = &ctx.for_organization(organization)?;
+    let mut results = Results::default();
 
-    // Load arguments
-    let args = Args::parse();
+    for check in ctx.config.checks(ctx.args) {
+        if let Checks::Organization(check) = check {
+            debug!(check = %check, "running check");
+            let result = check.run(ctx, organization).await;
+            results.record(&check, result, |finding| {
+                finding.with_check(&check).with_organization(organization)
+            });
+        }
+    }
 
-    let filter = EnvFilter::builder()
-        .with_default_directive(if args.debug {
-            Directive::from_str("ghsec=debug").unwrap()
-        } else {
-            LevelFilter::INFO.into()
-        })
-        .from_env_lossy();
+    Ok(results)
+}
 
-    let fmt = tracing_subscriber::fmt().with_env_filter(filter);
-    if args.json {
-        fmt.json().init()
-    } else {
-        fmt.compact().init();
-    };
+/// Exit code when findings at or above the `--fail-on` severity were reported
+const EXIT_FINDINGS: u8 = 1;
+
+/// Exit code when running checks failed
+const EXIT_ERROR: u8 = 2;
+
+async fn run(args: &Args) -> anyhow::Result<ExitCode> {
+    // Load configuration
+    let config = Config::load(args)?;
+
+    if let Some(Command::Config(ConfigCommand::Validate)) = &args.command {
+        match &config.path {
+            Some(path) => info!("{} is valid", path.display()),
+            None => info!("no configuration file found, using defaults"),
+        }
+
+        return Ok(ExitCode::SUCCESS);
+    }
+
+    let baseline = args.baseline.as_deref().map(Baseline::load).transpose()?;
 
     // Create client
+    let github_token = args
+        .github_token
+        .as_ref()
+        .ok_or_else(|| anyhow!("missing GitHub token"))?;
     let gh = Octocrab::builder()
-        .personal_token(args.github_token.unsecure().to_string())
+        .personal_token(github_token.unsecure().to_string())
         .build()?;
 
     // Print authentication information
@@ -122,36 +297,174 @@ async fn main() -> anyhow::Result<()> {
     info!("Logged in as {}", current_user.user().await?.login);
 
     // Context for running checks
-    let ctx = CheckCtx::new(&args, &gh);
-
-    if args.checks.has_repository_checks() {
-        // Get target repositories
-        let repos = current_user
-            .list_repos_for_authenticated_user()
-            .type_("owner")
-            .send()
-            .await?
-            .into_stream(&gh);
-        pin!(repos);
+    let ctx = CheckCtx::new(args, &gh, &config)?;
+    let mut findings = vec![];
+    let mut failed = false;
+
+    if config.has_repository_checks(args) {
+        // Get target repositories, either owned by the user or by the target organizations
+        let mut pages = vec![];
+        if args.orgs.is_empty() {
+            pages.push(
+                current_user
+                    .list_repos_for_authenticated_user()
+                    .type_("owner")
+                    .send()
+                    .await?,
+            );
+        } else {
+            for org in &args.orgs {
+                pages.push(gh.orgs(org).list_repos().send().await?);
+            }
+        }
 
         // Build a FuturesUnordered
         let mut tasks = FuturesUnordered::new();
-        while let Some(target_repo) = repos.try_next().await? {
-            if args.repository_names.matches(&target_repo.name) {
-                tasks.push(process_repo(&ctx, target_repo));
-            } else {
-                debug!(repository = %target_repo.name, "skipping repository not matching input pattern");
+        for page in pages {
+            let repos = page.into_stream(&gh);
+            pin!(repos);
+
+            while let Some(target_repo) = repos.try_next().await? {
+                if args.repository_names.matches(&target_repo.name) {
+                    tasks.push(process_repo(&ctx, target_repo));
+                } else {
+                    debug!(repository = %target_repo.name, "skipping repository not matching input pattern");
+                }
             }
         }
 
         // Poll it
         while let Some(result) = tasks.next().await {
-            if let Err(err) = result {
+            match result {
+                Ok(results) => {
+                    findings.extend(results.findings);
+                    failed |= results.failed;
+                }
+                Err(err) => {
+                    error!(?err, "error running check");
+                    failed = true;
+                }
+            }
+        }
+    }
+
+    // Finish with account or organization-level tasks
+    let results = if args.orgs.is_empty() {
+        vec![process_account(&ctx).await]
+    } else {
+        let mut results = vec![];
+        for org in &args.orgs {
+            results.push(process_org(&ctx, org).await);
+        }
+        results
+    };
+
+    for result in results {
+        match result {
+            Ok(results) => {
+                findings.extend(results.findings);
+                failed |= results.failed;
+            }
+            Err(err) => {
                 error!(?err, "error running check");
+                failed = true;
             }
         }
     }
 
-    // Finish with account-level tasks
-    process_account(&ctx).await
+    // Apply suppressions from the configuration file
+    suppressions::apply(&config.suppressions, &mut findings, Utc::now().date_naive());
+
+    if let Some(path) = &args.write_baseline {
+        let current: Vec<_> = findings
+            .iter()
+            .filter(|finding| finding.suppressed.is_none())
+            .cloned()
+            .collect();
+
+        Baseline::write(path, &current)?;
+        info!(
+            count = current.len(),
+            "wrote baseline to {}",
+            path.display()
+        );
+    }
+
+    // Only keep new findings
+    if let Some(baseline) = &baseline {
+        let count = findings.len();
+        findings.retain(|finding| !baseline.contains(finding));
+        info!(
+            count = count - findings.len(),
+            "ignoring findings already present in the baseline"
+        );
+    }
+
+    // Report findings
+    for finding in findings.iter().filter(|f| f.suppressed.is_none()) {
+        finding.log();
+    }
+
+    if let Some(path) = &args.sarif {
+        if path.as_os_str() == "-" {
+            sarif::write(std::io::stdout().lock(), config.all_checks(args), &findings)?;
+        } else {
+            sarif::write(File::create(path)?, config.all_checks(args), &findings)?;
+        }
+    }
+
+    if failed {
+        return Ok(ExitCode::from(EXIT_ERROR));
+    }
+
+    if let Some(threshold) = args.fail_on {
+        let count = findings
+            .iter()
+            .filter(|finding| finding.suppressed.is_none() && finding.severity >= threshold)
+            .count();
+
+        if count > 0 {
+            info!(
+                count,
+                "found {count} findings at or above {threshold} severity"
+            );
+            return Ok(ExitCode::from(EXIT_FINDINGS));
+        }
+    }
+
+    Ok(ExitCode::SUCCESS)
+}
+
+#[tokio::main(worker_threads = 4)]
+async fn main() -> ExitCode {
+    // Load variables from .env
+    dotenv::dotenv().ok();
+
+    // Load arguments
+    let args = Args::parse();
+
+    let filter = EnvFilter::builder()
+        .with_default_directive(if args.debug {
+            Directive::from_str("ghsec=debug").unwrap()
+        } else {
+            LevelFilter::INFO.into()
+        })
+        .from_env_lossy();
+
+    let fmt = tracing_subscriber::fmt()
+        .with_env_filter(filter)
+        .with_writer(std::io::stderr);
+    if args.json {
+        fmt.json().init()
+    } else {
+        fmt.compact().init();
+    };
+
+    match run(&args).await {
+        Ok(code) => code,
+        Err(err) => {
+            error!(?err, "error running checks");
+            ExitCode::from(EXIT_ERROR)
+        }
+    }
 }
@@ -0,0 +1,266 @@
+//! Export findings as a [SARIF 2.1.0](https://docs.oasis-open.org/sarif/sarif/v2.1.0/sarif-v2.1.0.html) log
+
+use std::{
+    collections::{HashMap, HashSet},
+    io::Write,
+};
+
+use serde::Serialize;
+
+use crate::{
+    checks::Checks,
+    findings::{Finding, Severity},
+};
+
+const SCHEMA: &str = "https://json.schemastore.org/sarif-2.1.0.json";
+
+#[derive(Debug, Serialize)]
+struct Log {
+    #[serde(rename = "$schema")]
+    schema: &'static str,
+    version: &'static str,
+    runs: Vec<Run>,
+}
+
+#[derive(Debug, Serialize)]
+struct Run {
+    tool: Tool,
+    results: Vec<SarifResult>,
+}
+
+#[derive(Debug, Serialize)]
+struct Tool {
+    driver: ToolComponent,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ToolComponent {
+    name: &'static str,
+    version: &'static str,
+    information_uri: &'static str,
+    rules: Vec<ReportingDescriptor>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ReportingDescriptor {
+    id: String,
+    short_description: Message,
+    full_description: Message,
+    help: Message,
+    help_uri: String,
+}
+
+#[derive(Debug, Serialize)]
+struct Message {
+    text: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    markdown: Option<String>,
+}
+
+impl Message {
+    fn text(text: impl Into<String>) -> Self {
+        Self {
+            text: text.into(),
+            markdown: None,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SarifResult {
+    rule_id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    rule_index: Option<usize>,
+    level: &'static str,
+    message: Message,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    locations: Vec<Location>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    suppressions: Vec<Suppression>,
+    partial_fingerprints: HashMap<&'static str, String>,
+    properties: ResultProperties,
+}
+
+#[derive(Debug, Serialize)]
+struct Suppression {
+    kind: &'static str,
+    justification: String,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Location {
+    physical_location: PhysicalLocation,
+    logical_locations: Vec<LogicalLocation>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct PhysicalLocation {
+    artifact_location: ArtifactLocation,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    region: Option<Region>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Region {
+    start_line: usize,
+}
+
+#[derive(Debug, Serialize)]
+struct ArtifactLocation {
+    uri: String,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct LogicalLocation {
+    fully_qualified_name: String,
+    kind: &'static str,
+}
+
+#[derive(Debug, Serialize)]
+struct ResultProperties {
+    rule: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    subject: Option<String>,
+    fixable: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    link: Option<String>,
+}
+
+impl From<Severity> for &'static str {
+    fn from(value: Severity) -> Self {
+        match value {
+            Severity::Info => "note",
+            Severity::Warning => "warning",
+            Severity::Error => "error",
+        }
+    }
+}
+
+impl From<&Checks> for ReportingDescriptor {
+    fn from(check: &Checks) -> Self {
+        let docs = check.docs();
+        let summary = docs
+            .split("\n\n")
+            .next()
+            .unwrap_or_default()
+            .replace('\n', " ");
+
+        Self {
+            id: check.to_string(),
+            short_description: Message::text(&summary),
+            full_description: Message::text(summary),
+            help: Message {
+                text: docs.to_owned(),
+                markdown: Some(docs.to_owned()),
+            },
+            help_uri: check.docs_url(),
+        }
+    }
+}
+
+impl From<&Finding> for Location {
+    fn from(finding: &Finding) -> Self {
+        let target = finding.target().unwrap_or_default();
+        let uri = match &finding.location {
+            Some(location) => format!("https://github.com/{target}/blob/HEAD/{}", location.path),
+            None => format!("https://github.com/{target}"),
+        };
+
+        Self {
+            physical_location: PhysicalLocation {
+                artifact_location: ArtifactLocation { uri },
+                region: finding
+                    .location
+                    .as_ref()
+                    .and_then(|location| location.line)
+                    .map(|start_line| Region { start_line }),
+            },
+            logical_locations: vec![LogicalLocation {
+                fully_qualified_name: target.to_owned(),
+                kind: "module",
+            }],
+        }
+    }
+}
+
+/// Write `findings` as a SARIF log, using `checks` and the checks that reported `findings` as the
+/// rules of the run
+pub fn write(
+    writer: impl Write,
+    checks: impl IntoIterator<Item = Checks>,
+    findings: &[Finding],
+) -> anyhow::Result<()> {
+    let mut ids = HashSet::new();
+    let rules: Vec<ReportingDescriptor> = checks
+        .into_iter()
+        .chain(
+            findings
+                .iter()
+                .filter_map(|finding| finding.check.parse().ok()),
+        )
+        .filter(|check| ids.insert(check.to_string()))
+        .map(|check| (&check).into())
+        .collect();
+    let rule_indices: HashMap<_, _> = rules
+        .iter()
+        .enumerate()
+        .map(|(index, rule)| (rule.id.clone(), index))
+        .collect();
+
+    let results = findings
+        .iter()
+        .map(|finding| SarifResult {
+            rule_id: finding.check.clone(),
+            rule_index: rule_indices.get(&finding.check).copied(),
+            level: finding.severity.into(),
+            message: Message::text(&finding.message),
+            locations: finding
+                .target()
+                .is_some()
+                .then(|| finding.into())
+                .into_iter()
+                .collect(),
+            suppressions: finding
+                .suppressed
+                .iter()
+                .map(|justification| Suppression {
+                    kind: "external",
+                    justification: justification.clone(),
+                })
+                .collect(),
+            partial_fingerprints: HashMap::from([("ghsec/v1", finding.fingerprint())]),
+            properties: ResultProperties {
+                rule: finding.rule.clone(),
+                subject: finding.subject.clone(),
+                fixable: finding.fixable,
+                link: finding.link.clone(),
+            },
+        })
+        .collect();
+
+    let log = Log {
+        schema: SCHEMA,
+        version: "2.1.0",
+        runs: vec![Run {
+            tool: Tool {
+                driver: ToolComponent {
+                    name: env!("CARGO_PKG_NAME"),
+                    version: env!("CARGO_PKG_VERSION"),
+                    information_uri: env!("CARGO_PKG_HOMEPAGE"),
+                    rules,
+                },
+            },
+            results,
+        }],
+    };
+
+    serde_json::to_writer_pretty(writer, &log)?;
+    Ok(())
+}
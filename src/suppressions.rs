@@ -0,0 +1,225 @@
+//! Suppression of findings using the configuration file
+//!
+//! Suppressions silence findings matching a repository pattern, a check and optionally a rule.
+//! Every suppression must be justified with a reason, and can be given an expiry date after which
+//! matching findings are reported again.
+//!
+//! Suppressed findings are also left untouched when running with `--fix`.
+
+use chrono::NaiveDate;
+use serde::{de::Error as _, Deserialize, Deserializer};
+use tracing::{debug, warn};
+
+use crate::{
+    checks::{AccountChecks, OrganizationChecks, RepositoryChecks},
+    config::matches_repository,
+    findings::Finding,
+};
+
+/// A suppression entry from the configuration file
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Suppression {
+    /// Repositories or organizations this suppression applies to. Supports globs. Applies to all
+    /// repositories, organizations and to account findings if omitted.
+    #[serde(default, deserialize_with = "deserialize_pattern")]
+    pub repositories: Option<glob::Pattern>,
+    /// Check whose findings are suppressed
+    pub check: String,
+    /// Rule whose findings are suppressed. Applies to all rules of the check if omitted.
+    pub rule: Option<String>,
+    /// Justification for this suppression
+    pub reason: String,
+    /// Date after which this suppression no longer applies
+    #[serde(default, deserialize_with = "deserialize_date")]
+    pub expires: Option<NaiveDate>,
+}
+
+impl Suppression {
+    /// Ensure this suppression refers to a known check and is justified
+    pub fn validate(&self) -> anyhow::Result<()> {
+        if self.check.parse::<RepositoryChecks>().is_err()
+            && self.check.parse::<AccountChecks>().is_err()
+            && self.check.parse::<OrganizationChecks>().is_err()
+        {
+            anyhow::bail!("unknown check {} in suppression", self.check);
+        }
+
+        if self.reason.trim().is_empty() {
+            anyhow::bail!("missing reason for suppression of {}", self.check);
+        }
+
+        Ok(())
+    }
+
+    /// `true` if this suppression applies to the given finding, regardless of its expiry date
+    fn matches(&self, finding: &Finding) -> bool {
+        self.applies_to(finding.target(), &finding.check, &finding.rule)
+    }
+
+    /// `true` if this suppression applies to findings of the given target, check and rule,
+    /// regardless of its expiry date
+    fn applies_to(&self, target: Option<&str>, check: &str, rule: &str) -> bool {
+        let repository_matches = match (&self.repositories, target) {
+            (None, _) => true,
+            (Some(pattern), Some(target)) => matches_repository(pattern, target),
+            (Some(_), None) => false,
+        };
+
+        repository_matches
+            && self.check == check
+            && self
+                .rule
+                .as_ref()
+                .is_none_or(|suppressed| suppressed == rule)
+    }
+
+    /// `true` if this suppression has expired at the given date
+    fn is_expired(&self, today: NaiveDate) -> bool {
+        self.expires.is_some_and(|expires| expires < today)
+    }
+}
+
+/// `true` if findings of the given target, check and rule are silenced by an active suppression.
+///
+/// Checks use this before running with `--fix`, so suppressed findings are not fixed.
+pub fn is_suppressed(
+    suppressions: &[Suppression],
+    target: Option<&str>,
+    check: &str,
+    rule: &str,
+    today: NaiveDate,
+) -> bool {
+    suppressions.iter().any(|suppression| {
+        suppression.applies_to(target, check, rule) && !suppression.is_expired(today)
+    })
+}
+
+/// Mark findings matching an active suppression as suppressed
+pub fn apply(suppressions: &[Suppression], findings: &mut [Finding], today: NaiveDate) {
+    for finding in findings {
+        for suppression in suppressions.iter().filter(|s| s.matches(finding)) {
+            if suppression.is_expired(today) {
+                warn!(
+                    check = finding.check,
+                    repository = finding.repository,
+                    rule = finding.rule,
+                    expires = %suppression.expires.unwrap_or_default(),
+                    reason = suppression.reason,
+                    "suppression expired, reporting finding again"
+                );
+            } else {
+                debug!(
+                    check = finding.check,
+                    repository = finding.repository,
+                    rule = finding.rule,
+                    reason = suppression.reason,
+                    "suppressed finding"
+                );
+                finding.suppressed = Some(suppression.reason.clone());
+                break;
+            }
+        }
+    }
+}
+
+fn deserialize_pattern<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Option<glob::Pattern>, D::Error> {
+    crate::config::deserialize_pattern(deserializer).map(Some)
+}
+
+fn deserialize_date<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Option<NaiveDate>, D::Error> {
+    let datetime = toml::value::Datetime::deserialize(deserializer)?;
+    let date = datetime
+        .date
+        .ok_or_else(|| D::Error::custom("expected a date"))?;
+
+    NaiveDate::from_ymd_opt(date.year.into(), date.month.into(), date.day.into())
+        .map(Some)
+        .ok_or_else(|| D::Error::custom("invalid date"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::findings::Severity;
+
+    fn suppressions(toml: &str) -> Vec<Suppression> {
+        #[derive(Deserialize)]
+        struct File {
+            suppressions: Vec<Suppression>,
+        }
+
+        toml::from_str::<File>(toml).unwrap().suppressions
+    }
+
+    fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap()
+    }
+
+    const SUPPRESSIONS: &str = r#"
+[[suppressions]]
+repositories = "octocat/*"
+check = "deploy_keys"
+rule = "write_access"
+reason = "deploys from CI"
+expires = 2026-06-30
+"#;
+
+    #[test]
+    fn suppressed_until_expiry() {
+        let suppressions = suppressions(SUPPRESSIONS);
+        let suppressed = |target, rule, today| {
+            is_suppressed(&suppressions, Some(target), "deploy_keys", rule, today)
+        };
+
+        assert!(suppressed(
+            "octocat/hello",
+            "write_access",
+            date(2026, 6, 30)
+        ));
+        assert!(!suppressed(
+            "octocat/hello",
+            "write_access",
+            date(2026, 7, 1)
+        ));
+        assert!(!suppressed("octocat/hello", "unused_key", date(2026, 6, 1)));
+        assert!(!suppressed(
+            "github/hello",
+            "write_access",
+            date(2026, 6, 1)
+        ));
+    }
+
+    #[test]
+    fn apply_marks_active_suppressions() {
+        let suppressions = suppressions(SUPPRESSIONS);
+        let finding = Finding::new("write_access", Severity::Warning, "write access")
+            .with_check("deploy_keys")
+            .with_repository("octocat/hello");
+
+        let mut findings = vec![finding.clone()];
+        apply(&suppressions, &mut findings, date(2026, 6, 1));
+        assert_eq!(findings[0].suppressed.as_deref(), Some("deploys from CI"));
+
+        let mut findings = vec![finding];
+        apply(&suppressions, &mut findings, date(2026, 7, 1));
+        assert_eq!(findings[0].suppressed, None);
+    }
+
+    #[test]
+    fn missing_reason() {
+        let suppressions = suppressions(
+            r#"
+[[suppressions]]
+check = "deploy_keys"
+reason = " "
+"#,
+        );
+
+        assert!(suppressions[0].validate().is_err());
+    }
+}